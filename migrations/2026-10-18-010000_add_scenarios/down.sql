@@ -0,0 +1,2 @@
+DROP TABLE scenario_subcomponent;
+DROP TABLE scenario;
@@ -0,0 +1,22 @@
+CREATE TABLE scenario
+(
+    id         varchar(25)  NOT NULL,
+    course_id  varchar(25)  NOT NULL,
+    name       varchar(191) NOT NULL,
+    created_at timestamptz  NOT NULL DEFAULT now(),
+    PRIMARY KEY (id),
+    CONSTRAINT fk_course_owns_scenario FOREIGN KEY (course_id) REFERENCES course (id) ON DELETE CASCADE
+);
+
+CREATE TABLE scenario_subcomponent
+(
+    scenario_id            varchar(25)  NOT NULL,
+    subcomponent_id        varchar(25)  NOT NULL,
+    grade_value_percentage numeric(5,4) NOT NULL,
+    PRIMARY KEY (scenario_id, subcomponent_id),
+    CONSTRAINT fk_scenario_owns_scenario_subcomponent FOREIGN KEY (scenario_id) REFERENCES scenario (id) ON DELETE CASCADE,
+    CONSTRAINT fk_subcomponent_has_scenario_subcomponent FOREIGN KEY (subcomponent_id) REFERENCES course_subcomponent (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_fk_scenario_course ON scenario (course_id);
+CREATE INDEX idx_fk_scenario_subcomponent ON scenario_subcomponent (subcomponent_id);
@@ -0,0 +1,11 @@
+CREATE TABLE course_share
+(
+    code       varchar(8)  NOT NULL,
+    course_id  varchar(25) NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    expires_at timestamptz,
+    PRIMARY KEY (code),
+    CONSTRAINT fk_course_owns_share FOREIGN KEY (course_id) REFERENCES course (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_fk_course_share ON course_share (course_id);
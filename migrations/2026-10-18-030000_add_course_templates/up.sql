@@ -0,0 +1,18 @@
+CREATE TABLE course_template
+(
+    id                 varchar(25)  NOT NULL,
+    user_id            varchar(191) NOT NULL,
+    name               varchar(191) NOT NULL,
+    institution        varchar(191) NOT NULL,
+    long_name          varchar(191),
+    course_code_name   varchar(10),
+    course_code_number varchar(10),
+    color              varchar(7)   NOT NULL DEFAULT '',
+    structure          json         NOT NULL,
+    created_at         timestamptz  NOT NULL DEFAULT now(),
+    PRIMARY KEY (id),
+    CONSTRAINT fk_user_owns_course_template FOREIGN KEY (user_id) REFERENCES gk_user (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_fk_course_template_user ON course_template (user_id);
+CREATE INDEX idx_course_template_code ON course_template (upper(course_code_name), course_code_number);
@@ -0,0 +1,18 @@
+CREATE TABLE view_grant
+(
+    id         varchar(25)  NOT NULL,
+    token      varchar(32)  NOT NULL,
+    user_id    varchar(191) NOT NULL,
+    block_id   varchar(25)  NOT NULL,
+    course_id  varchar(25),
+    label      varchar(191) NOT NULL DEFAULT '',
+    created_at timestamptz  NOT NULL DEFAULT now(),
+    expires_at timestamptz,
+    PRIMARY KEY (id),
+    CONSTRAINT uq_view_grant_token UNIQUE (token),
+    CONSTRAINT fk_user_owns_view_grant FOREIGN KEY (user_id) REFERENCES gk_user (id) ON DELETE CASCADE,
+    CONSTRAINT fk_block_has_view_grant FOREIGN KEY (block_id) REFERENCES study_block (id) ON DELETE CASCADE,
+    CONSTRAINT fk_course_has_view_grant FOREIGN KEY (course_id) REFERENCES course (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_fk_view_grant_user ON view_grant (user_id);
@@ -0,0 +1,4 @@
+DROP INDEX idx_course_origin;
+
+ALTER TABLE course DROP COLUMN share_grade_distribution;
+ALTER TABLE course DROP COLUMN origin_id;
@@ -0,0 +1,4 @@
+ALTER TABLE course ADD origin_id varchar(25) NULL DEFAULT NULL;
+ALTER TABLE course ADD share_grade_distribution bool NOT NULL DEFAULT false;
+
+CREATE INDEX idx_course_origin ON course (origin_id);
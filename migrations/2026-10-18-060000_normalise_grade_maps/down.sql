@@ -0,0 +1,2 @@
+-- Normalising grade maps can't be undone, and normalised grade maps are still valid JSON.
+SELECT 1;
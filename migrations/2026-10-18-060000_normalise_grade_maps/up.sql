@@ -0,0 +1,15 @@
+-- Grade maps were previously accepted as arbitrary JSON. Keep only entries the server now
+-- accepts (a threshold between 0 and 1 mapped to a non-empty label), and fall back to the
+-- default grade map for any user left without a single valid entry.
+UPDATE gk_user
+SET grade_map = COALESCE(
+    (SELECT json_object_agg(entries.threshold, entries.label)
+     FROM (SELECT DISTINCT ON (trim_scale(btrim(key)::numeric)) trim_scale(btrim(key)::numeric)::text AS threshold,
+                                                                   left(btrim(value #>> '{}'), 32)         AS label
+           FROM json_each(CASE WHEN json_typeof(gk_user.grade_map) = 'object' THEN gk_user.grade_map ELSE '{}'::json END)
+           WHERE CASE WHEN btrim(key) ~ '^([0-9]+\.?[0-9]*|\.[0-9]+)$' THEN btrim(key)::numeric <= 1 ELSE false END
+             AND json_typeof(value) = 'string'
+             AND btrim(value #>> '{}') <> ''
+           ORDER BY trim_scale(btrim(key)::numeric), key) entries),
+    '{"0.4": "D", "0.5": "C-", "0.6": "C+", "0.7": "B", "0.8": "A-", "0.9": "A+", "0.55": "C", "0.65": "B-", "0.75": "B+", "0.85": "A"}'::json
+);
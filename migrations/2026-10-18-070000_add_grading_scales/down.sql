@@ -0,0 +1,5 @@
+DROP INDEX idx_fk_course_grading_scale;
+ALTER TABLE course DROP CONSTRAINT fk_course_grading_scale;
+ALTER TABLE course DROP COLUMN grading_scale_id;
+
+DROP TABLE grading_scale;
@@ -0,0 +1,30 @@
+CREATE TABLE grading_scale
+(
+    id         varchar(25)  NOT NULL,
+    user_id    varchar(191),
+    name       varchar(191) NOT NULL,
+    grade_map  json         NOT NULL,
+    created_at timestamptz  NOT NULL DEFAULT now(),
+    PRIMARY KEY (id),
+    CONSTRAINT fk_user_owns_grading_scale FOREIGN KEY (user_id) REFERENCES gk_user (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_fk_grading_scale_user ON grading_scale (user_id);
+
+-- Preset scales aren't owned by any user, and are available to everyone.
+INSERT INTO grading_scale (id, user_id, name, grade_map)
+VALUES ('preset_nz', NULL, 'New Zealand',
+        '{"0.4": "D", "0.5": "C-", "0.55": "C", "0.6": "C+", "0.65": "B-", "0.7": "B", "0.75": "B+", "0.8": "A-", "0.85": "A", "0.9": "A+"}'),
+       ('preset_us', NULL, 'United States (4.0)',
+        '{"0": "F", "0.6": "D-", "0.63": "D", "0.67": "D+", "0.7": "C-", "0.73": "C", "0.77": "C+", "0.8": "B-", "0.83": "B", "0.87": "B+", "0.9": "A-", "0.93": "A"}'),
+       ('preset_uk', NULL, 'United Kingdom (classes)',
+        '{"0": "Fail", "0.4": "Third", "0.5": "Lower Second (2:2)", "0.6": "Upper Second (2:1)", "0.7": "First"}'),
+       ('preset_au', NULL, 'Australia',
+        '{"0": "N", "0.5": "P", "0.65": "C", "0.75": "D", "0.85": "HD"}');
+
+ALTER TABLE course
+    ADD COLUMN grading_scale_id varchar(25);
+ALTER TABLE course
+    ADD CONSTRAINT fk_course_grading_scale FOREIGN KEY (grading_scale_id) REFERENCES grading_scale (id) ON DELETE SET NULL;
+
+CREATE INDEX idx_fk_course_grading_scale ON course (grading_scale_id);
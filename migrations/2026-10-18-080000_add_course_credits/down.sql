@@ -0,0 +1,11 @@
+UPDATE grading_scale
+SET grade_map = '{"0.4": "D", "0.5": "C-", "0.55": "C", "0.6": "C+", "0.65": "B-", "0.7": "B", "0.75": "B+", "0.8": "A-", "0.85": "A", "0.9": "A+"}'
+WHERE id = 'preset_nz';
+UPDATE grading_scale
+SET grade_map = '{"0": "F", "0.6": "D-", "0.63": "D", "0.67": "D+", "0.7": "C-", "0.73": "C", "0.77": "C+", "0.8": "B-", "0.83": "B", "0.87": "B+", "0.9": "A-", "0.93": "A"}'
+WHERE id = 'preset_us';
+UPDATE grading_scale
+SET grade_map = '{"0": "N", "0.5": "P", "0.65": "C", "0.75": "D", "0.85": "HD"}'
+WHERE id = 'preset_au';
+
+ALTER TABLE course DROP COLUMN credits;
@@ -0,0 +1,13 @@
+ALTER TABLE course
+    ADD COLUMN credits numeric(6, 2) NOT NULL DEFAULT 1;
+
+-- Add GPA points to the preset scales that have a common GPA system.
+UPDATE grading_scale
+SET grade_map = '{"0.4": {"label": "D", "gpaPoints": "0"}, "0.5": {"label": "C-", "gpaPoints": "1"}, "0.55": {"label": "C", "gpaPoints": "2"}, "0.6": {"label": "C+", "gpaPoints": "3"}, "0.65": {"label": "B-", "gpaPoints": "4"}, "0.7": {"label": "B", "gpaPoints": "5"}, "0.75": {"label": "B+", "gpaPoints": "6"}, "0.8": {"label": "A-", "gpaPoints": "7"}, "0.85": {"label": "A", "gpaPoints": "8"}, "0.9": {"label": "A+", "gpaPoints": "9"}}'
+WHERE id = 'preset_nz';
+UPDATE grading_scale
+SET grade_map = '{"0": {"label": "F", "gpaPoints": "0"}, "0.6": {"label": "D-", "gpaPoints": "0.7"}, "0.63": {"label": "D", "gpaPoints": "1"}, "0.67": {"label": "D+", "gpaPoints": "1.3"}, "0.7": {"label": "C-", "gpaPoints": "1.7"}, "0.73": {"label": "C", "gpaPoints": "2"}, "0.77": {"label": "C+", "gpaPoints": "2.3"}, "0.8": {"label": "B-", "gpaPoints": "2.7"}, "0.83": {"label": "B", "gpaPoints": "3"}, "0.87": {"label": "B+", "gpaPoints": "3.3"}, "0.9": {"label": "A-", "gpaPoints": "3.7"}, "0.93": {"label": "A", "gpaPoints": "4"}}'
+WHERE id = 'preset_us';
+UPDATE grading_scale
+SET grade_map = '{"0": {"label": "N", "gpaPoints": "0"}, "0.5": {"label": "P", "gpaPoints": "4"}, "0.65": {"label": "C", "gpaPoints": "5"}, "0.75": {"label": "D", "gpaPoints": "6"}, "0.85": {"label": "HD", "gpaPoints": "7"}}'
+WHERE id = 'preset_au';
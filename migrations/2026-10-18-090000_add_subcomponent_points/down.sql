@@ -0,0 +1,4 @@
+ALTER TABLE course_subcomponent
+    DROP CONSTRAINT chk_subcomponent_points,
+    DROP COLUMN points_possible,
+    DROP COLUMN points_earned;
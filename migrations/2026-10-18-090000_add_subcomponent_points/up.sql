@@ -0,0 +1,4 @@
+ALTER TABLE course_subcomponent
+    ADD COLUMN points_earned   numeric(10, 4),
+    ADD COLUMN points_possible numeric(10, 4),
+    ADD CONSTRAINT chk_subcomponent_points CHECK ((points_earned IS NULL) = (points_possible IS NULL));
@@ -0,0 +1,2 @@
+ALTER TABLE course_subcomponent
+    DROP COLUMN weighting;
@@ -0,0 +1,2 @@
+ALTER TABLE course_subcomponent
+    ADD COLUMN weighting numeric(5, 4);
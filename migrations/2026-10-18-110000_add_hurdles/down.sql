@@ -0,0 +1,3 @@
+ALTER TABLE course DROP COLUMN hurdle;
+
+ALTER TABLE course_component DROP COLUMN hurdle;
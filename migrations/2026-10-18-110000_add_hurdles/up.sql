@@ -0,0 +1,5 @@
+ALTER TABLE course_component
+    ADD COLUMN hurdle numeric(5, 4);
+
+ALTER TABLE course
+    ADD COLUMN hurdle numeric(5, 4);
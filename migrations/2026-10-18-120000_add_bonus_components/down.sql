@@ -0,0 +1,3 @@
+ALTER TABLE course_component
+    DROP COLUMN maximum_score,
+    DROP COLUMN is_bonus;
@@ -0,0 +1,3 @@
+ALTER TABLE course_component
+    ADD COLUMN is_bonus      boolean       NOT NULL DEFAULT false,
+    ADD COLUMN maximum_score numeric(5, 4) NOT NULL DEFAULT 1;
@@ -0,0 +1,4 @@
+DROP INDEX idx_course_subcomponent_due_at;
+
+ALTER TABLE course_subcomponent
+    DROP COLUMN due_at;
@@ -0,0 +1,4 @@
+ALTER TABLE course_subcomponent
+    ADD COLUMN due_at timestamptz;
+
+CREATE INDEX idx_course_subcomponent_due_at ON course_subcomponent (due_at) WHERE due_at IS NOT NULL;
@@ -0,0 +1,10 @@
+CREATE TABLE calendar_feed
+(
+    user_id    varchar(191) NOT NULL,
+    token      varchar(32)  NOT NULL,
+    created_at timestamptz  NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id),
+    CONSTRAINT fk_user_owns_calendar_feed FOREIGN KEY (user_id) REFERENCES gk_user (id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_calendar_feed_token ON calendar_feed (token);
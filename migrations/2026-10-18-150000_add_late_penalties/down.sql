@@ -0,0 +1,7 @@
+ALTER TABLE course_subcomponent
+    DROP COLUMN extended_due_at,
+    DROP COLUMN submitted_at;
+
+ALTER TABLE course_component
+    DROP COLUMN late_penalty_cap,
+    DROP COLUMN late_penalty_per_day;
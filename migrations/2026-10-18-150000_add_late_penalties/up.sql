@@ -0,0 +1,7 @@
+ALTER TABLE course_component
+    ADD COLUMN late_penalty_per_day numeric(5, 4),
+    ADD COLUMN late_penalty_cap numeric(5, 4);
+
+ALTER TABLE course_subcomponent
+    ADD COLUMN submitted_at timestamptz,
+    ADD COLUMN extended_due_at timestamptz;
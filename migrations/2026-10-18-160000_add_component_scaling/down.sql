@@ -0,0 +1,3 @@
+ALTER TABLE course_component
+    DROP COLUMN is_scaling_enabled,
+    DROP COLUMN scaling;
@@ -0,0 +1,3 @@
+ALTER TABLE course_component
+    ADD COLUMN scaling json,
+    ADD COLUMN is_scaling_enabled boolean NOT NULL DEFAULT true;
@@ -0,0 +1,3 @@
+ALTER TABLE course
+    DROP COLUMN target_grade,
+    DROP COLUMN target;
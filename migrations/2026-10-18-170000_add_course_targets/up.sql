@@ -0,0 +1,3 @@
+ALTER TABLE course
+    ADD COLUMN target numeric(5, 4),
+    ADD COLUMN target_grade varchar(32);
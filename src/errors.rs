@@ -1,6 +1,7 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use bigdecimal::BigDecimal;
 use diesel::result::Error;
 use log::error;
 use std::convert::From;
@@ -13,6 +14,7 @@ pub type AppResult<R> = Result<R, AppError>;
 pub struct AppError {
     pub(crate) status_code: StatusCode,
     pub(crate) description: String,
+    pub(crate) details: Option<serde_json::Value>,
 }
 
 impl From<Error> for AppError {
@@ -28,12 +30,14 @@ impl AppError {
         AppError {
             status_code: StatusCode::BAD_REQUEST,
             description: format!("Redirect URL '{}' is not allowed.", redirect_url),
+            details: None,
         }
     }
     pub fn resource_access_denied() -> AppError {
         AppError {
             status_code: StatusCode::UNAUTHORIZED,
             description: "You are not allowed to access that resource.".to_string(),
+            details: None,
         }
     }
     pub fn database_ise(e: Error) -> AppError {
@@ -44,18 +48,36 @@ impl AppError {
         AppError {
             status_code: StatusCode::INTERNAL_SERVER_ERROR,
             description: "There was an error. Please try again later.".to_string(),
+            details: None,
         }
     }
     pub fn resource_not_found() -> AppError {
         AppError {
             status_code: StatusCode::NOT_FOUND,
             description: "That resource was not found.".to_string(),
+            details: None,
+        }
+    }
+    pub fn invalid_weighting(current_total: BigDecimal) -> AppError {
+        AppError {
+            status_code: StatusCode::BAD_REQUEST,
+            description: "Course components must add up to 100%.".to_string(),
+            details: Some(json!({ "currentTotal": current_total })),
+        }
+    }
+    pub fn invalid_subcomponent_weighting(current_total: BigDecimal) -> AppError {
+        AppError {
+            status_code: StatusCode::BAD_REQUEST,
+            description: "Subcomponent weightings must add up to 100% of their component."
+                .to_string(),
+            details: Some(json!({ "currentTotal": current_total })),
         }
     }
     pub fn bad_request<D: ToString>(message: D) -> AppError {
         AppError {
             status_code: StatusCode::BAD_REQUEST,
             description: message.to_string(),
+            details: None,
         }
     }
 }
@@ -67,13 +89,13 @@ impl<T> From<AppError> for Result<T, AppError> {
 }
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (
-            self.status_code,
-            Json(json![{
-                "type": self.status_code.as_u16(),
-                "error": self.description
-            }]),
-        )
-            .into_response()
+        let mut body = json![{
+            "type": self.status_code.as_u16(),
+            "error": self.description
+        }];
+        if let Some(details) = self.details {
+            body["details"] = details;
+        }
+        (self.status_code, Json(body)).into_response()
     }
 }
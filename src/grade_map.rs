@@ -0,0 +1,278 @@
+use bigdecimal::{BigDecimal, One, Zero};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Json;
+use serde::de::Error;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const MAXIMUM_ENTRIES: usize = 100;
+const MAXIMUM_LABEL_LENGTH: usize = 32;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradeMapEntry {
+    pub threshold: BigDecimal,
+    pub label: String,
+    /// The number of GPA points this grade is worth, if the user has configured one.
+    pub gpa_points: Option<BigDecimal>,
+}
+
+/// Maps the minimum percentage required for each grade to that grade's label.
+///
+/// Serialized as a JSON object from threshold to label (e.g. `{"0.5": "C-"}`), which is how
+/// grade maps have always been stored and sent to clients. Grades worth GPA points are
+/// serialized as an object instead of a label (e.g. `{"0.5": {"label": "C-", "gpaPoints": "1"}}`).
+#[derive(AsExpression, FromSqlRow, Clone, Debug, PartialEq)]
+#[diesel(sql_type = Json)]
+pub struct GradeMap {
+    /// Sorted from the highest threshold to the lowest.
+    entries: Vec<GradeMapEntry>,
+}
+
+impl GradeMap {
+    /// Parses and validates a grade map. Every threshold must be a distinct number
+    /// between 0 and 1, and every label must be a non-empty string.
+    /// GPA points are optional, but must not be negative.
+    pub fn parse(value: &serde_json::Value) -> Result<GradeMap, String> {
+        let Some(object) = value.as_object() else {
+            return Err("Grade map must be an object of thresholds to grades.".to_string());
+        };
+        if object.is_empty() {
+            return Err("Grade map must contain at least one grade.".to_string());
+        }
+        if object.len() > MAXIMUM_ENTRIES {
+            return Err(format!(
+                "Grade map must not contain more than {} grades.",
+                MAXIMUM_ENTRIES
+            ));
+        }
+
+        let mut entries = object
+            .iter()
+            .map(|(threshold, grade)| {
+                let Ok(parsed_threshold) = threshold.trim().parse::<BigDecimal>() else {
+                    return Err(format!("Grade threshold '{}' is not a number.", threshold));
+                };
+                if parsed_threshold < BigDecimal::zero() || parsed_threshold > BigDecimal::one() {
+                    return Err(format!(
+                        "Grade threshold '{}' must be between 0 and 1.",
+                        threshold
+                    ));
+                }
+                let (label, gpa_points) = match grade {
+                    serde_json::Value::Object(grade) => (
+                        grade.get("label").and_then(|l| l.as_str()),
+                        grade.get("gpaPoints").filter(|p| !p.is_null()),
+                    ),
+                    _ => (grade.as_str(), None),
+                };
+                let label = label.map(str::trim).unwrap_or_default();
+                if label.is_empty() {
+                    return Err(format!(
+                        "Grade at threshold '{}' must have a label.",
+                        threshold
+                    ));
+                }
+                if label.chars().count() > MAXIMUM_LABEL_LENGTH {
+                    return Err(format!(
+                        "Grade labels must not be longer than {} characters.",
+                        MAXIMUM_LABEL_LENGTH
+                    ));
+                }
+                let gpa_points = gpa_points
+                    .map(|points| parse_gpa_points(threshold, points))
+                    .transpose()?;
+                Ok(GradeMapEntry {
+                    threshold: parsed_threshold.normalized(),
+                    label: label.to_string(),
+                    gpa_points,
+                })
+            })
+            .collect::<Result<Vec<GradeMapEntry>, String>>()?;
+
+        entries.sort_by(|a, b| b.threshold.cmp(&a.threshold));
+        if entries
+            .windows(2)
+            .any(|pair| pair[0].threshold == pair[1].threshold)
+        {
+            return Err("Grade thresholds must be unique.".to_string());
+        }
+
+        Ok(GradeMap { entries })
+    }
+
+    pub fn entries(&self) -> &[GradeMapEntry] {
+        &self.entries
+    }
+
+    /// Resolves a percentage to the highest grade it reaches,
+    /// or `None` if it's below every threshold.
+    pub fn entry_for(&self, percentage: &BigDecimal) -> Option<&GradeMapEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.threshold <= *percentage)
+    }
+
+    /// Resolves a percentage to the label of the highest grade it reaches,
+    /// or `None` if it's below every threshold.
+    pub fn grade_for(&self, percentage: &BigDecimal) -> Option<&str> {
+        self.entry_for(percentage).map(|entry| entry.label.as_str())
+    }
+
+    /// Resolves a grade label to the minimum percentage required for it.
+    pub fn threshold_for(&self, label: &str) -> Option<&BigDecimal> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.label == label)
+            .map(|entry| &entry.threshold)
+            .min()
+    }
+}
+
+fn parse_gpa_points(threshold: &str, points: &serde_json::Value) -> Result<BigDecimal, String> {
+    let parsed_points = match points {
+        serde_json::Value::Number(n) => n.to_string().parse::<BigDecimal>().ok(),
+        serde_json::Value::String(s) => s.trim().parse::<BigDecimal>().ok(),
+        _ => None,
+    };
+    match parsed_points {
+        Some(p) if p >= BigDecimal::zero() => Ok(p.normalized()),
+        _ => Err(format!(
+            "GPA points at threshold '{}' must be a number that isn't negative.",
+            threshold
+        )),
+    }
+}
+
+impl Default for GradeMap {
+    fn default() -> Self {
+        GradeMap::parse(&serde_json::json!({
+          "0.4": "D",
+          "0.5": "C-",
+          "0.6": "C+",
+          "0.7": "B",
+          "0.8": "A-",
+          "0.9": "A+",
+          "0.55": "C",
+          "0.65": "B-",
+          "0.75": "B+",
+          "0.85": "A",
+        }))
+        .expect("Default grade map is valid")
+    }
+}
+
+impl Serialize for GradeMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+        for entry in &self.entries {
+            match &entry.gpa_points {
+                Some(gpa_points) => map.serialize_entry(
+                    &entry.threshold.to_string(),
+                    &serde_json::json!({ "label": entry.label, "gpaPoints": gpa_points }),
+                )?,
+                None => map.serialize_entry(&entry.threshold.to_string(), &entry.label)?,
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for GradeMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        GradeMap::parse(&serde_json::Value::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl FromSql<Json, Pg> for GradeMap {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let value = <serde_json::Value as FromSql<Json, Pg>>::from_sql(bytes)?;
+        Ok(GradeMap::parse(&value)?)
+    }
+}
+
+impl ToSql<Json, Pg> for GradeMap {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        serde_json::to_writer(out, self)?;
+        Ok(IsNull::No)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(value: &str) -> BigDecimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_trims_thresholds_and_labels() {
+        let grade_map = GradeMap::parse(&serde_json::json!({
+            " 0.5 ": " Pass ",
+            "0.85": "High Distinction",
+        }))
+        .unwrap();
+
+        assert_eq!(grade_map.entries()[0].threshold, decimal("0.85"));
+        assert_eq!(grade_map.entries()[1].threshold, decimal("0.5"));
+        assert_eq!(grade_map.entries()[1].label, "Pass");
+    }
+
+    #[test]
+    fn parse_rejects_thresholds_that_are_equal_after_normalising() {
+        for value in [
+            serde_json::json!({ "0.5": "C", "0.50": "C-" }),
+            serde_json::json!({ "0.5": "C", " 0.5": "C-" }),
+        ] {
+            assert_eq!(
+                GradeMap::parse(&value),
+                Err("Grade thresholds must be unique.".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn parse_rejects_thresholds_out_of_range() {
+        for threshold in ["-0.1", "1.01", "50"] {
+            assert_eq!(
+                GradeMap::parse(&serde_json::json!({ threshold: "A" })),
+                Err(format!(
+                    "Grade threshold '{}' must be between 0 and 1.",
+                    threshold
+                ))
+            );
+        }
+        assert!(GradeMap::parse(&serde_json::json!({ "0": "F", "1": "A+" })).is_ok());
+    }
+
+    #[test]
+    fn parse_rejects_invalid_thresholds_and_labels() {
+        assert_eq!(
+            GradeMap::parse(&serde_json::json!({ "   ": "A" })),
+            Err("Grade threshold '   ' is not a number.".to_string())
+        );
+        assert_eq!(
+            GradeMap::parse(&serde_json::json!({ "0.5": "  " })),
+            Err("Grade at threshold '0.5' must have a label.".to_string())
+        );
+        assert_eq!(
+            GradeMap::parse(&serde_json::json!({ "0.5": { "label": "C", "gpaPoints": -1 } })),
+            Err("GPA points at threshold '0.5' must be a number that isn't negative.".to_string())
+        );
+        assert!(GradeMap::parse(&serde_json::json!({})).is_err());
+        assert!(GradeMap::parse(&serde_json::json!(["A"])).is_err());
+    }
+
+    #[test]
+    fn resolves_grades_and_thresholds() {
+        let grade_map = GradeMap::default();
+
+        assert_eq!(grade_map.grade_for(&decimal("0.9")), Some("A+"));
+        assert_eq!(grade_map.grade_for(&decimal("0.6499")), Some("C+"));
+        assert_eq!(grade_map.grade_for(&decimal("0.3")), None);
+        assert_eq!(grade_map.threshold_for("B-"), Some(&decimal("0.65")));
+    }
+}
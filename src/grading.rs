@@ -0,0 +1,691 @@
+use bigdecimal::{BigDecimal, One, RoundingMode, ToPrimitive, Zero};
+use serde::Serialize;
+use time::Duration;
+
+use crate::models::{CourseComponent, CourseSubcomponent};
+use crate::scaling::ScalingRule;
+
+/// Number of decimal places grades are reported to, matching the `numeric(5,4)` columns
+/// that raw subcomponent grades are stored in.
+pub const GRADE_SCALE: i64 = 4;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GradeSummary {
+    /// The grade locked in so far, treating every incomplete subcomponent as zero.
+    pub actual: BigDecimal,
+    /// The grade if the student keeps performing at their current level,
+    /// or `None` if nothing has been completed yet.
+    pub projected: Option<BigDecimal>,
+    /// The best grade still achievable, treating every incomplete subcomponent as full marks.
+    pub maximum: BigDecimal,
+}
+
+impl GradeSummary {
+    fn rounded(self) -> GradeSummary {
+        GradeSummary {
+            actual: round_grade(&self.actual),
+            projected: self.projected.as_ref().map(round_grade),
+            maximum: round_grade(&self.maximum),
+        }
+    }
+}
+
+pub fn round_grade(value: &BigDecimal) -> BigDecimal {
+    value.with_scale_round(GRADE_SCALE, RoundingMode::HalfUp)
+}
+
+/// The raw score of a completed subcomponent, before any late penalty. Scores entered as
+/// points are computed exactly, rather than from the rounded percentage stored alongside them.
+pub fn subcomponent_score(subcomponent: &CourseSubcomponent) -> BigDecimal {
+    match (&subcomponent.points_earned, &subcomponent.points_possible) {
+        (Some(earned), Some(possible)) if !possible.is_zero() => earned / possible,
+        _ => subcomponent.grade_value_percentage.clone(),
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LatePenalty {
+    pub subcomponent_id: String,
+    /// Whole days late, counting any part of a day as a full day.
+    pub days_late: i64,
+    /// The fraction of full marks deducted.
+    pub penalty: BigDecimal,
+    /// The subcomponent's score after scaling and the penalty. The raw score is left on the
+    /// subcomponent.
+    pub grade_value_percentage: BigDecimal,
+}
+
+/// The number of days a subcomponent was submitted late, and the fraction of full marks
+/// deducted for it, or `None` if the subcomponent wasn't submitted late, or the component
+/// doesn't penalise late submissions.
+fn days_late_and_penalty(
+    component: &CourseComponent,
+    subcomponent: &CourseSubcomponent,
+) -> Option<(i64, BigDecimal)> {
+    let per_day = component.late_penalty_per_day.as_ref()?;
+    let due_at = subcomponent.extended_due_at.or(subcomponent.due_at)?;
+    let late_by = subcomponent.submitted_at? - due_at;
+    if !subcomponent.is_completed || !late_by.is_positive() {
+        return None;
+    }
+
+    let mut days_late = late_by.whole_days();
+    if late_by > Duration::days(days_late) {
+        days_late += 1;
+    }
+    let penalty = per_day * BigDecimal::from(days_late);
+    match &component.late_penalty_cap {
+        Some(cap) if penalty > *cap => Some((days_late, cap.clone())),
+        _ => Some((days_late, penalty)),
+    }
+}
+
+/// The component's scaling rule, if scaling is turned on.
+pub fn active_scaling(component: &CourseComponent) -> Option<&ScalingRule> {
+    component
+        .scaling
+        .as_ref()
+        .filter(|_| component.is_scaling_enabled)
+}
+
+/// The score of a completed subcomponent after the component's scaling rule,
+/// kept between zero and the component's maximum score.
+pub fn scaled_score(component: &CourseComponent, subcomponent: &CourseSubcomponent) -> BigDecimal {
+    let score = subcomponent_score(subcomponent);
+    match active_scaling(component) {
+        Some(rule) => rule
+            .apply(&score)
+            .clamp(BigDecimal::zero(), component.maximum_score.clone()),
+        None => score,
+    }
+}
+
+/// The score of a completed subcomponent after scaling and any late penalty, which is what
+/// counts towards its component. Scores never go below zero.
+pub fn adjusted_score(
+    component: &CourseComponent,
+    subcomponent: &CourseSubcomponent,
+) -> BigDecimal {
+    let score = scaled_score(component, subcomponent);
+    match days_late_and_penalty(component, subcomponent) {
+        Some((_, penalty)) => (score - penalty).max(BigDecimal::zero()),
+        None => score,
+    }
+}
+
+/// Describes the late penalty applied to a subcomponent, if any.
+pub fn late_penalty(
+    component: &CourseComponent,
+    subcomponent: &CourseSubcomponent,
+) -> Option<LatePenalty> {
+    let (days_late, penalty) = days_late_and_penalty(component, subcomponent)?;
+    Some(LatePenalty {
+        subcomponent_id: subcomponent.id.clone(),
+        days_late,
+        penalty: round_grade(&penalty),
+        grade_value_percentage: round_grade(&adjusted_score(component, subcomponent)),
+    })
+}
+
+/// Averages scores by their weightings, or equally if the weightings add up to zero.
+fn weighted_average(scores: &[(BigDecimal, BigDecimal)]) -> BigDecimal {
+    let total_weighting = scores.iter().map(|(_, w)| w).sum::<BigDecimal>();
+    if total_weighting.is_zero() {
+        return scores.iter().map(|(s, _)| s).sum::<BigDecimal>()
+            / BigDecimal::from(scores.len() as i64);
+    }
+    scores.iter().map(|(s, w)| s * w).sum::<BigDecimal>() / total_weighting
+}
+
+/// Averages the subcomponents of a component after dropping the lowest scores.
+///
+/// Incomplete subcomponents are assumed to score `fill`. If `fill` is `None`, incomplete
+/// subcomponents are assumed to score the average of the completed subcomponents, so that
+/// the lowest completed scores are only dropped if they would still be dropped once the rest
+/// of the component is completed at the same level. `None` is returned if nothing has been
+/// completed. The number of subcomponents dropped is clamped so that at least one is always
+/// counted.
+///
+/// If the subcomponents have weightings, the average is weighted, and the subcomponents that
+/// remain after dropping are re-weighted so that they make up the whole component.
+/// Subcomponents are dropped in order of score, and between subcomponents with the same score,
+/// the more heavily weighted one is dropped first.
+pub fn component_average(
+    component: &CourseComponent,
+    subcomponents: &[CourseSubcomponent],
+    fill: Option<&BigDecimal>,
+) -> Option<BigDecimal> {
+    let weighted = subcomponents.iter().all(|s| s.weighting.is_some());
+    let weighting_of = |s: &CourseSubcomponent| match (weighted, &s.weighting) {
+        (true, Some(w)) => w.clone(),
+        _ => BigDecimal::one(),
+    };
+
+    let completed = subcomponents
+        .iter()
+        .filter(|s| s.is_completed)
+        .map(|s| (adjusted_score(component, s), weighting_of(s)))
+        .collect::<Vec<(BigDecimal, BigDecimal)>>();
+    let fill = match fill {
+        Some(f) => f.clone(),
+        None if completed.is_empty() => return None,
+        None => weighted_average(&completed),
+    };
+
+    let mut scores = completed;
+    scores.extend(
+        subcomponents
+            .iter()
+            .filter(|s| !s.is_completed)
+            .map(|s| (fill.clone(), weighting_of(s))),
+    );
+    if scores.is_empty() {
+        return None;
+    }
+    scores.sort_by(|(a, a_weighting), (b, b_weighting)| {
+        a.cmp(b).then_with(|| b_weighting.cmp(a_weighting))
+    });
+
+    let drop = component
+        .number_of_subcomponents_to_drop_lowest
+        .clamp(0, scores.len() as i32 - 1) as usize;
+    Some(weighted_average(&scores[drop..]))
+}
+
+fn component_grade_unrounded(
+    component: &CourseComponent,
+    subcomponents: &[CourseSubcomponent],
+) -> GradeSummary {
+    GradeSummary {
+        actual: component_average(component, subcomponents, Some(&BigDecimal::zero()))
+            .unwrap_or_else(BigDecimal::zero),
+        projected: component_average(component, subcomponents, None),
+        maximum: component_average(component, subcomponents, Some(&component.maximum_score))
+            .unwrap_or_else(|| component.maximum_score.clone()),
+    }
+}
+
+/// The amount a component adds to its course's grade, given the component's grade.
+/// Bonus components add at most their weighting.
+fn component_contribution(component: &CourseComponent, grade: &BigDecimal) -> BigDecimal {
+    match component.is_bonus && *grade > BigDecimal::one() {
+        true => component.subject_weighting.clone(),
+        false => &component.subject_weighting * grade,
+    }
+}
+
+/// Computes the actual, projected and maximum grade of a single component.
+pub fn grade_component(
+    component: &CourseComponent,
+    subcomponents: &[CourseSubcomponent],
+) -> GradeSummary {
+    component_grade_unrounded(component, subcomponents).rounded()
+}
+
+/// Computes the actual, projected and maximum grade of a course from its components.
+///
+/// The projected grade only considers components that have at least one completed
+/// subcomponent, re-weighted so that those components make up the whole course.
+/// Bonus components are added on top, rather than being re-weighted.
+pub fn grade_course<'a>(
+    components: impl IntoIterator<Item = (&'a CourseComponent, &'a [CourseSubcomponent])>,
+) -> GradeSummary {
+    let mut actual = BigDecimal::zero();
+    let mut maximum = BigDecimal::zero();
+    let mut projected = BigDecimal::zero();
+    let mut projected_weighting = BigDecimal::zero();
+    let mut projected_bonus = BigDecimal::zero();
+
+    for (component, subcomponents) in components {
+        let grade = component_grade_unrounded(component, subcomponents);
+
+        actual += component_contribution(component, &grade.actual);
+        maximum += component_contribution(component, &grade.maximum);
+        if let Some(p) = grade.projected {
+            match component.is_bonus {
+                true => projected_bonus += component_contribution(component, &p),
+                false => {
+                    projected += &component.subject_weighting * p;
+                    projected_weighting += &component.subject_weighting;
+                }
+            }
+        }
+    }
+
+    GradeSummary {
+        actual,
+        projected: (!projected_weighting.is_zero())
+            .then(|| projected / projected_weighting + projected_bonus),
+        maximum,
+    }
+    .rounded()
+}
+
+/// Computes the final grade of a course if every incomplete subcomponent scores `fill`,
+/// or its component's maximum score if that's lower.
+pub fn course_grade_with_fill<'a>(
+    components: impl IntoIterator<Item = (&'a CourseComponent, &'a [CourseSubcomponent])>,
+    fill: &BigDecimal,
+) -> BigDecimal {
+    components
+        .into_iter()
+        .map(|(component, subcomponents)| {
+            let fill = fill.min(&component.maximum_score);
+            component_contribution(
+                component,
+                &component_average(component, subcomponents, Some(fill))
+                    .unwrap_or_else(|| fill.clone()),
+            )
+        })
+        .sum()
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum HurdleStatus {
+    /// The hurdle has been met, even if every incomplete subcomponent scores zero.
+    Met,
+    /// The hurdle hasn't been decided yet, and the current projection meets it
+    /// (or nothing has been completed yet).
+    Pending,
+    /// The hurdle hasn't been decided yet, but the current projection doesn't meet it.
+    AtRisk,
+    /// The hurdle can't be met, even with full marks on every incomplete subcomponent.
+    Missed,
+}
+
+impl HurdleStatus {
+    fn is_failing(self) -> bool {
+        matches!(self, HurdleStatus::AtRisk | HurdleStatus::Missed)
+    }
+}
+
+/// Compares a grade against a hurdle.
+pub fn hurdle_status(hurdle: &BigDecimal, grade: &GradeSummary) -> HurdleStatus {
+    if grade.actual >= *hurdle {
+        HurdleStatus::Met
+    } else if grade.maximum < *hurdle {
+        HurdleStatus::Missed
+    } else if grade.projected.as_ref().is_some_and(|p| p < hurdle) {
+        HurdleStatus::AtRisk
+    } else {
+        HurdleStatus::Pending
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum HurdleOutcome {
+    /// Every hurdle has been met, or is projected to be met.
+    Pass,
+    /// The overall grade is projected to miss the course's hurdle.
+    Fail,
+    /// The overall grade is projected to pass, but a component hurdle is projected to be missed.
+    FailDueToHurdle,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentHurdle {
+    pub component_id: String,
+    pub hurdle: BigDecimal,
+    pub status: HurdleStatus,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseHurdles {
+    /// The status of the course's overall hurdle, if it has one.
+    pub course: Option<HurdleStatus>,
+    pub components: Vec<ComponentHurdle>,
+    pub outcome: HurdleOutcome,
+}
+
+/// Checks a course's overall hurdle and the hurdles on each of its components against their
+/// grades, or returns `None` if the course doesn't have any hurdles.
+pub fn course_hurdles<'a>(
+    course_hurdle: Option<&BigDecimal>,
+    course_grade: &GradeSummary,
+    components: impl IntoIterator<Item = (&'a CourseComponent, &'a GradeSummary)>,
+) -> Option<CourseHurdles> {
+    let course = course_hurdle.map(|h| hurdle_status(h, course_grade));
+    let components = components
+        .into_iter()
+        .filter_map(|(component, grade)| {
+            component.hurdle.as_ref().map(|h| ComponentHurdle {
+                component_id: component.id.clone(),
+                hurdle: h.clone(),
+                status: hurdle_status(h, grade),
+            })
+        })
+        .collect::<Vec<ComponentHurdle>>();
+    if course.is_none() && components.is_empty() {
+        return None;
+    }
+
+    let outcome = if course.is_some_and(HurdleStatus::is_failing) {
+        HurdleOutcome::Fail
+    } else if components.iter().any(|c| c.status.is_failing()) {
+        HurdleOutcome::FailDueToHurdle
+    } else {
+        HurdleOutcome::Pass
+    };
+    Some(CourseHurdles {
+        course,
+        components,
+        outcome,
+    })
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "status", content = "requiredScore")]
+pub enum RequiredScore {
+    /// The target has already been reached, even if every incomplete subcomponent scores zero.
+    Secured,
+    /// The minimum score needed on every incomplete subcomponent to reach the target.
+    Required(BigDecimal),
+    /// The target can't be reached, even with full marks on every incomplete subcomponent.
+    Unachievable,
+}
+
+/// Finds the minimum uniform score needed on every incomplete subcomponent for the course
+/// to reach `target`, to the precision of [GRADE_SCALE]. Scores above 100% are only
+/// considered if a component with incomplete subcomponents offers extra credit.
+pub fn required_score<'a>(
+    components: &[(&'a CourseComponent, &'a [CourseSubcomponent])],
+    target: &BigDecimal,
+) -> RequiredScore {
+    let grade_with = |fill: &BigDecimal| course_grade_with_fill(components.iter().copied(), fill);
+    let highest_fill = components
+        .iter()
+        .filter(|(_, subcomponents)| {
+            subcomponents.is_empty() || subcomponents.iter().any(|s| !s.is_completed)
+        })
+        .map(|(component, _)| &component.maximum_score)
+        .max()
+        .cloned()
+        .unwrap_or_else(BigDecimal::one);
+
+    if grade_with(&BigDecimal::zero()) >= *target {
+        return RequiredScore::Secured;
+    }
+    if grade_with(&highest_fill) < *target {
+        return RequiredScore::Unachievable;
+    }
+
+    // The final grade never decreases as the fill score increases,
+    // so binary search for the lowest fill score on the grade scale that reaches the target.
+    let step = |n: i64| BigDecimal::new(n.into(), GRADE_SCALE);
+    let (mut low, mut high) = (
+        0i64,
+        (highest_fill * BigDecimal::from(10i64.pow(GRADE_SCALE as u32)))
+            .with_scale_round(0, RoundingMode::Floor)
+            .to_i64()
+            .unwrap_or(0),
+    );
+    while high - low > 1 {
+        let mid = (low + high) / 2;
+        if grade_with(&step(mid)) >= *target {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    RequiredScore::Required(step(high))
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TargetStatus {
+    /// The target has been reached, even if every incomplete subcomponent scores zero.
+    Secured,
+    /// The target can still be reached, and the current projection reaches it
+    /// (or nothing has been completed yet).
+    Achievable,
+    /// The target can still be reached, but the current projection falls short of it.
+    AtRisk,
+    /// The target can't be reached, even with full marks on every incomplete subcomponent.
+    Unachievable,
+}
+
+impl TargetStatus {
+    pub fn is_at_risk(self) -> bool {
+        matches!(self, TargetStatus::AtRisk | TargetStatus::Unachievable)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetProgress {
+    pub status: TargetStatus,
+    /// The minimum score needed on every incomplete subcomponent to reach the target,
+    /// unless the target has already been secured or can't be reached.
+    pub required_score: Option<BigDecimal>,
+}
+
+/// Compares a course's grade against the user's target for the course.
+pub fn target_progress<'a>(
+    target: &BigDecimal,
+    course_grade: &GradeSummary,
+    components: &[(&'a CourseComponent, &'a [CourseSubcomponent])],
+) -> TargetProgress {
+    let (status, required_score) = match required_score(components, target) {
+        RequiredScore::Secured => (TargetStatus::Secured, None),
+        RequiredScore::Unachievable => (TargetStatus::Unachievable, None),
+        RequiredScore::Required(score) => {
+            match course_grade.projected.as_ref().is_some_and(|p| p < target) {
+                true => (TargetStatus::AtRisk, Some(score)),
+                false => (TargetStatus::Achievable, Some(score)),
+            }
+        }
+    };
+    TargetProgress {
+        status,
+        required_score,
+    }
+}
+
+/// Computes a grade point average, weighting each course's GPA points by its credits.
+/// Returns `None` if the courses aren't worth any credits.
+pub fn weighted_gpa<'a>(
+    courses: impl IntoIterator<Item = (&'a BigDecimal, &'a BigDecimal)>,
+) -> Option<BigDecimal> {
+    let (points, credits) = courses.into_iter().fold(
+        (BigDecimal::zero(), BigDecimal::zero()),
+        |(points, credits), (gpa_points, course_credits)| {
+            (
+                points + gpa_points * course_credits,
+                credits + course_credits,
+            )
+        },
+    );
+    (!credits.is_zero()).then(|| round_grade(&(points / credits)))
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GradeDistribution {
+    pub count: usize,
+    pub lower_quartile: BigDecimal,
+    pub median: BigDecimal,
+    pub upper_quartile: BigDecimal,
+    /// Number of grades in each tenth of the grade range, from 0-10% up to 90-100%.
+    /// Grades of 100% or more are counted in the last bucket.
+    pub histogram: Vec<usize>,
+}
+
+/// Summarises a set of grades, or returns `None` if there are fewer than `minimum_count`
+/// grades, so that no individual's grade can be inferred from the summary.
+pub fn grade_distribution(
+    mut grades: Vec<BigDecimal>,
+    minimum_count: usize,
+) -> Option<GradeDistribution> {
+    if grades.is_empty() || grades.len() < minimum_count {
+        return None;
+    }
+    grades.sort();
+
+    // Linearly interpolates between the closest ranks.
+    let quantile = |numerator: i64, denominator: i64| {
+        let position =
+            BigDecimal::from((grades.len() as i64 - 1) * numerator) / BigDecimal::from(denominator);
+        let lower = position.with_scale_round(0, RoundingMode::Floor);
+        let fraction = &position - &lower;
+        let index = lower.to_usize().unwrap_or(0);
+        let base = &grades[index];
+        match grades.get(index + 1) {
+            Some(next) => round_grade(&(base + (next - base) * fraction)),
+            None => round_grade(base),
+        }
+    };
+
+    let mut histogram = vec![0; 10];
+    for grade in &grades {
+        let bucket = (grade * BigDecimal::from(10))
+            .with_scale_round(0, RoundingMode::Floor)
+            .to_i64()
+            .unwrap_or(0)
+            .clamp(0, 9) as usize;
+        histogram[bucket] += 1;
+    }
+
+    Some(GradeDistribution {
+        count: grades.len(),
+        lower_quartile: quantile(1, 4),
+        median: quantile(1, 2),
+        upper_quartile: quantile(3, 4),
+        histogram,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(value: &str) -> BigDecimal {
+        value.parse().unwrap()
+    }
+
+    fn component(drop_lowest: i32) -> CourseComponent {
+        CourseComponent {
+            id: "component".to_string(),
+            name: "Labs".to_string(),
+            name_of_subcomponent_singular: "Lab".to_string(),
+            number_of_subcomponents_to_drop_lowest: drop_lowest,
+            course_id: "course".to_string(),
+            subject_weighting: BigDecimal::one(),
+            sequence_number: Some(1),
+            hurdle: None,
+            is_bonus: false,
+            maximum_score: BigDecimal::one(),
+            late_penalty_per_day: None,
+            late_penalty_cap: None,
+            scaling: None,
+            is_scaling_enabled: true,
+        }
+    }
+
+    /// A subcomponent with the given score, or an incomplete one if `score` is `None`.
+    fn subcomponent(score: Option<&str>, weighting: Option<&str>) -> CourseSubcomponent {
+        CourseSubcomponent {
+            id: "subcomponent".to_string(),
+            component_id: "component".to_string(),
+            grade_value_percentage: score.map(decimal).unwrap_or_else(BigDecimal::zero),
+            is_completed: score.is_some(),
+            number_in_sequence: 1,
+            override_name: None,
+            points_earned: None,
+            points_possible: None,
+            weighting: weighting.map(decimal),
+            due_at: None,
+            submitted_at: None,
+            extended_due_at: None,
+        }
+    }
+
+    #[test]
+    fn projection_drops_lowest_after_filling_incomplete_subcomponents() {
+        let subcomponents = [
+            subcomponent(Some("0.5"), None),
+            subcomponent(Some("0.6"), None),
+            subcomponent(Some("0.9"), None),
+            subcomponent(None, None),
+            subcomponent(None, None),
+        ];
+        let grade = grade_component(&component(2), &subcomponents);
+
+        // The incomplete labs are projected at the current average of 2/3, so only the 0.5
+        // and 0.6 are dropped: (2/3 + 2/3 + 0.9) / 3.
+        assert_eq!(grade.projected, Some(decimal("0.7444")));
+        assert_eq!(grade.actual, decimal("0.6667"));
+        assert_eq!(grade.maximum, decimal("0.9667"));
+    }
+
+    #[test]
+    fn projection_is_none_when_nothing_is_completed() {
+        let subcomponents = [subcomponent(None, None), subcomponent(None, None)];
+        let grade = grade_component(&component(1), &subcomponents);
+
+        assert_eq!(grade.projected, None);
+        assert_eq!(grade.actual, decimal("0"));
+        assert_eq!(grade.maximum, decimal("1"));
+    }
+
+    #[test]
+    fn drop_lowest_always_counts_at_least_one_subcomponent() {
+        let subcomponents = [
+            subcomponent(Some("0.4"), None),
+            subcomponent(Some("0.8"), None),
+        ];
+        let grade = grade_component(&component(5), &subcomponents);
+
+        assert_eq!(grade.projected, Some(decimal("0.8")));
+        assert_eq!(grade.actual, decimal("0.8"));
+    }
+
+    #[test]
+    fn weighted_drop_lowest_drops_heavier_subcomponent_on_ties() {
+        let subcomponents = [
+            subcomponent(Some("1"), Some("0.5")),
+            subcomponent(Some("0.5"), Some("0.3")),
+            subcomponent(Some("0.5"), Some("0.2")),
+        ];
+        let grade = grade_component(&component(1), &subcomponents);
+
+        // (1 * 0.5 + 0.5 * 0.2) / 0.7
+        assert_eq!(grade.projected, Some(decimal("0.8571")));
+    }
+
+    #[test]
+    fn required_score_considers_extra_credit() {
+        let extra_credit = CourseComponent {
+            maximum_score: decimal("1.5"),
+            ..component(0)
+        };
+        let subcomponents = [subcomponent(Some("1"), None), subcomponent(None, None)];
+        let components = [(&extra_credit, subcomponents.as_slice())];
+
+        assert_eq!(
+            required_score(&components, &decimal("1.2")),
+            RequiredScore::Required(decimal("1.4"))
+        );
+        assert_eq!(
+            required_score(&components, &decimal("1.3")),
+            RequiredScore::Unachievable
+        );
+    }
+
+    #[test]
+    fn weighted_average_falls_back_to_equal_when_remaining_weightings_are_zero() {
+        let subcomponents = [
+            subcomponent(Some("0.2"), Some("1")),
+            subcomponent(Some("0.6"), Some("0")),
+            subcomponent(Some("0.8"), Some("0")),
+        ];
+        let grade = grade_component(&component(1), &subcomponents);
+
+        assert_eq!(grade.projected, Some(decimal("0.7")));
+    }
+}
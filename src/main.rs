@@ -1,12 +1,17 @@
 mod config;
 mod errors;
+mod grade_map;
+mod grading;
 mod middleware;
 mod models;
 mod routes;
+mod scaling;
 mod schema;
 use crate::config::Config;
 use crate::errors::AppError;
-use crate::middleware::auth::{check_authorization, validate_ownership_of_route_assets};
+use crate::middleware::auth::{
+    check_authorization, check_calendar_feed, check_view_grant, validate_ownership_of_route_assets,
+};
 use crate::routes::health;
 use axum::http::header::AUTHORIZATION;
 use axum::http::StatusCode;
@@ -45,6 +50,7 @@ impl ServerState {
         self.db_pool.get().map_err(|_e| AppError {
             status_code: StatusCode::INTERNAL_SERVER_ERROR,
             description: "Unable to connect to the Gradekeeper database.".to_string(),
+            details: None,
         })
     }
 }
@@ -107,10 +113,21 @@ async fn main() {
         .route("/api/users/me", get(api::users::me::get_user))
         .route("/api/users/me", post(api::users::me::update_user))
         .route("/api/users/me", axum::routing::delete(api::users::me::delete_user))
+        .route("/api/users/me/at-risk", get(api::users::at_risk::get_at_risk_courses))
+        .route("/api/users/me/feed", get(api::users::feed::get_calendar_feed))
+        .route("/api/users/me/feed", post(api::users::feed::rotate_calendar_feed))
+        .route("/api/users/me/feed", axum::routing::delete(api::users::feed::delete_calendar_feed))
+        .route("/api/users/me/gpa", get(api::users::gpa::get_gpa))
+        .route("/api/users/me/upcoming", get(api::users::upcoming::get_upcoming))
+        .route("/api/users/me/grants", get(api::users::grants::get_view_grants))
+        .route("/api/users/me/grants/{grant_id}", axum::routing::delete(api::users::grants::revoke_view_grant))
         // Blocks
         .route("/api/block/create", post(api::block::create::create_block))
+        .route("/api/block/{block_id}", post(api::block::block_id::update_block))
         .route("/api/block/{block_id}", axum::routing::delete(api::block::block_id::delete_block))
         .route("/api/block/{block_id}/import", post(api::block::_block_id::import::import_course))
+        .route("/api/block/{block_id}/grant", post(api::users::grants::create_view_grant))
+        .route("/api/block/{block_id}/rollover", post(api::block::_block_id::rollover::rollover_block))
 
         // Courses
         .route("/api/block/{block_id}/course/create", post(api::block::_block_id::course::create::create_course))
@@ -118,15 +135,72 @@ async fn main() {
         .route("/api/block/{block_id}/course/{course_id}", axum::routing::delete(api::block::_block_id::course::course_id::delete_course))
         .route("/api/block/{block_id}/course/{course_id}", post(api::block::_block_id::course::course_id::update_course))
         .route("/api/block/{block_id}/course/{course_id}/order", post(api::block::_block_id::course::_course_id::order::update_course_component_order))
+        .route("/api/block/{block_id}/course/{course_id}/distribution", get(api::block::_block_id::course::_course_id::distribution::get_course_distribution))
+        .route("/api/block/{block_id}/course/{course_id}/grant", post(api::users::grants::create_view_grant))
+        .route("/api/block/{block_id}/course/{course_id}/move", post(api::block::_block_id::course::_course_id::transfer::move_course))
+        .route("/api/block/{block_id}/course/{course_id}/copy", post(api::block::_block_id::course::_course_id::transfer::copy_course))
+        .route("/api/block/{block_id}/course/{course_id}/publish", post(api::block::_block_id::course::_course_id::publish::publish_course))
+        .route("/api/block/{block_id}/course/{course_id}/scale", post(api::block::_block_id::course::_course_id::scale::set_course_grading_scale))
+        .route("/api/block/{block_id}/course/{course_id}/scenario/create", post(api::block::_block_id::course::_course_id::scenario::create::create_scenario))
+        .route("/api/block/{block_id}/course/{course_id}/scenario/{scenario_id}", post(api::block::_block_id::course::_course_id::scenario::scenario_id::update_scenario))
+        .route("/api/block/{block_id}/course/{course_id}/scenario/{scenario_id}", axum::routing::delete(api::block::_block_id::course::_course_id::scenario::scenario_id::delete_scenario))
+        .route("/api/block/{block_id}/course/{course_id}/scenario/{scenario_id}/promote", post(api::block::_block_id::course::_course_id::scenario::_scenario_id::promote::promote_scenario))
+        .route("/api/block/{block_id}/course/{course_id}/share", get(api::block::_block_id::course::_course_id::share::list::get_course_shares))
+        .route("/api/block/{block_id}/course/{course_id}/share/create", post(api::block::_block_id::course::_course_id::share::create::create_course_share))
+        .route("/api/block/{block_id}/course/{course_id}/share/{share_code}", axum::routing::delete(api::block::_block_id::course::_course_id::share::share_code::revoke_course_share))
+        .route("/api/block/{block_id}/course/{course_id}/target", get(api::block::_block_id::course::_course_id::target::get_course_target))
+        .route("/api/block/{block_id}/course/{course_id}/target", post(api::block::_block_id::course::_course_id::target::set_course_target))
+        .route("/api/block/{block_id}/course/{course_id}/target", axum::routing::delete(api::block::_block_id::course::_course_id::target::delete_course_target))
         
         // Components
+        .route("/api/block/{block_id}/course/{course_id}/component/create",
+               post(api::block::_block_id::course::_course_id::component::create::create_course_component)
+        )
         .route("/api/block/{block_id}/course/{course_id}/component/{component_id}",
                post(api::block::_block_id::course::_course_id::component::component_id::update_course_component)
         )
+        .route("/api/block/{block_id}/course/{course_id}/component/{component_id}",
+               axum::routing::delete(api::block::_block_id::course::_course_id::component::component_id::delete_course_component)
+        )
+        .route("/api/block/{block_id}/course/{course_id}/component/{component_id}/subcomponent/create",
+               post(api::block::_block_id::course::_course_id::component::_component_id::subcomponent::create::create_subcomponent)
+        )
+        .route("/api/block/{block_id}/course/{course_id}/component/{component_id}/subcomponent/{subcomponent_id}",
+               post(api::block::_block_id::course::_course_id::component::_component_id::subcomponent::subcomponent_id::update_subcomponent)
+        )
+        .route("/api/block/{block_id}/course/{course_id}/component/{component_id}/subcomponent/{subcomponent_id}",
+               axum::routing::delete(api::block::_block_id::course::_course_id::component::_component_id::subcomponent::subcomponent_id::delete_subcomponent)
+        )
+
+        // Templates
+        .route("/api/template/search", get(api::template::search::search_templates))
+        .route("/api/template/{template_id}", get(api::template::template_id::get_template))
+        .route("/api/template/{template_id}", axum::routing::delete(api::template::template_id::delete_template))
+
+        // Grading scales
+        .route("/api/scale", get(api::scale::list::get_grading_scales))
+        .route("/api/scale/create", post(api::scale::create::create_grading_scale))
+        .route("/api/scale/{scale_id}", get(api::scale::scale_id::get_grading_scale))
+        .route("/api/scale/{scale_id}", post(api::scale::scale_id::update_grading_scale))
+        .route("/api/scale/{scale_id}", axum::routing::delete(api::scale::scale_id::delete_grading_scale))
         .layer(axum::middleware::from_fn(validate_ownership_of_route_assets))
         .layer(axum::middleware::from_fn(check_authorization))
         // End authorised section
 
+        // Read-only views, authorised by view grant links instead of a session
+        .merge(
+            Router::new()
+                .route("/api/view/{grant_token}", get(api::view::get_shared_view))
+                .layer(axum::middleware::from_fn(check_view_grant)),
+        )
+
+        // Calendar feeds, authorised by a secret feed token instead of a session
+        .merge(
+            Router::new()
+                .route("/api/calendar/{feed_token}", get(api::calendar::get_calendar))
+                .layer(axum::middleware::from_fn(check_calendar_feed)),
+        )
+
         // Login
         .route("/api/auth/login", get(api::auth::login::handle_login_request))
         .route("/api/auth/callback", get(api::auth::callback::handle_auth_callback))
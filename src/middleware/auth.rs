@@ -6,25 +6,36 @@ use axum::response::Response;
 use axum::Extension;
 
 use std::sync::Arc;
+use time::OffsetDateTime;
 
 use axum::body::Body;
-use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+};
 use google_oauth::AsyncClient;
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::Deserialize;
 
 use crate::errors::{AppError, AppResult};
-use crate::models::{Course, CourseComponent, CourseSubcomponent, StudyBlock};
+use crate::models::{
+    CalendarFeed, Course, CourseComponent, CourseShare, CourseSubcomponent, Scenario, StudyBlock,
+    ViewGrant,
+};
 use crate::routes::api::auth::callback::Session;
+use crate::routes::api::block::_block_id::course::_course_id::share::normalise_share_code;
+use crate::schema::calendar_feed::dsl::calendar_feed;
 use crate::schema::course::block_id;
 use crate::schema::course::dsl::course;
 use crate::schema::course_component::course_id;
 use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_share::dsl::course_share;
 use crate::schema::course_subcomponent::component_id;
 use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::schema::scenario::dsl::scenario;
 
 use crate::schema::study_block::dsl::study_block;
 use crate::schema::study_block::{id, user_id};
+use crate::schema::view_grant::dsl::view_grant;
 use crate::ServerState;
 
 #[derive(Deserialize)]
@@ -33,6 +44,8 @@ pub struct RouteAssetIdentifiers {
     course_id: Option<String>,
     component_id: Option<String>,
     subcomponent_id: Option<String>,
+    scenario_id: Option<String>,
+    share_code: Option<String>,
 }
 pub async fn validate_ownership_of_route_assets(
     Path(route_asset_ids): Path<RouteAssetIdentifiers>,
@@ -43,12 +56,7 @@ pub async fn validate_ownership_of_route_assets(
 ) -> Result<Response, AppError> {
     let con = &mut state.get_db_con()?;
     if let Some(_block_id) = &route_asset_ids.block_id {
-        if study_block
-            .filter(id.eq(_block_id).and(user_id.eq(&session.id)))
-            .select(StudyBlock::as_select())
-            .first(con)
-            .is_err()
-        {
+        if !user_owns_block(con, _block_id, &session.id) {
             return Err(AppError::resource_access_denied());
         }
     }
@@ -73,7 +81,7 @@ pub async fn validate_ownership_of_route_assets(
             .filter(
                 crate::schema::course_component::id
                     .eq(_component_id)
-                    .and(course_id.eq(route_asset_ids.course_id.unwrap())),
+                    .and(course_id.eq(route_asset_ids.course_id.as_ref().unwrap())),
             )
             .select(CourseComponent::as_select())
             .first(con)
@@ -97,9 +105,48 @@ pub async fn validate_ownership_of_route_assets(
             return Err(AppError::resource_access_denied());
         }
     }
+
+    if let Some(_scenario_id) = &route_asset_ids.scenario_id {
+        if scenario
+            .filter(crate::schema::scenario::id.eq(_scenario_id).and(
+                crate::schema::scenario::course_id.eq(route_asset_ids.course_id.as_ref().unwrap()),
+            ))
+            .select(Scenario::as_select())
+            .first(con)
+            .is_err()
+        {
+            return Err(AppError::resource_access_denied());
+        }
+    }
+
+    if let Some(_share_code) = &route_asset_ids.share_code {
+        if course_share
+            .filter(
+                crate::schema::course_share::code
+                    .eq(normalise_share_code(_share_code))
+                    .and(
+                        crate::schema::course_share::course_id
+                            .eq(route_asset_ids.course_id.unwrap()),
+                    ),
+            )
+            .select(CourseShare::as_select())
+            .first(con)
+            .is_err()
+        {
+            return Err(AppError::resource_access_denied());
+        }
+    }
     Ok(next.run(request).await)
 }
 
+pub(crate) fn user_owns_block(con: &mut PgConnection, _block_id: &str, _user_id: &str) -> bool {
+    study_block
+        .filter(id.eq(_block_id).and(user_id.eq(_user_id)))
+        .select(StudyBlock::as_select())
+        .first(con)
+        .is_ok()
+}
+
 pub async fn check_authorization(
     Extension(state): Extension<Arc<ServerState>>,
     Extension(google_client): Extension<Arc<AsyncClient>>,
@@ -118,6 +165,7 @@ pub async fn check_authorization(
         .ok_or_else(|| AppError {
             status_code: StatusCode::UNAUTHORIZED,
             description: "No authorization header present.".to_string(),
+            details: None,
         })?;
 
     let session = try_decode_session(token, &state, &google_client).await?;
@@ -126,6 +174,68 @@ pub async fn check_authorization(
     Ok(next.run(request).await)
 }
 
+#[derive(Deserialize)]
+pub struct ViewGrantIdentifier {
+    grant_token: String,
+}
+
+/// Authorises read-only access through a view grant link, in place of a session.
+/// Injects the `Arc<ViewGrant>` so route handlers know what the link is scoped to.
+pub async fn check_view_grant(
+    Path(ViewGrantIdentifier { grant_token }): Path<ViewGrantIdentifier>,
+    Extension(state): Extension<Arc<ServerState>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> AppResult<Response> {
+    let con = &mut state.get_db_con()?;
+    let grant = view_grant
+        .filter(crate::schema::view_grant::token.eq(&grant_token))
+        .filter(
+            crate::schema::view_grant::expires_at
+                .is_null()
+                .or(crate::schema::view_grant::expires_at.gt(OffsetDateTime::now_utc())),
+        )
+        .select(ViewGrant::as_select())
+        .first(con)
+        .map_err(|_| AppError {
+            status_code: StatusCode::FORBIDDEN,
+            description: "That link is invalid or has been revoked.".to_string(),
+            details: None,
+        })?;
+    request.extensions_mut().insert(Arc::new(grant));
+
+    Ok(next.run(request).await)
+}
+
+#[derive(Deserialize)]
+pub struct CalendarFeedIdentifier {
+    feed_token: String,
+}
+
+/// Authorises access to a user's calendar feed through its secret token, in place of a session,
+/// since calendar apps can't send an `Authorization` header.
+/// Injects the `Arc<CalendarFeed>` so route handlers know whose feed it is.
+pub async fn check_calendar_feed(
+    Path(CalendarFeedIdentifier { feed_token }): Path<CalendarFeedIdentifier>,
+    Extension(state): Extension<Arc<ServerState>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> AppResult<Response> {
+    let con = &mut state.get_db_con()?;
+    let feed = calendar_feed
+        .filter(crate::schema::calendar_feed::token.eq(&feed_token))
+        .select(CalendarFeed::as_select())
+        .first(con)
+        .map_err(|_| AppError {
+            status_code: StatusCode::FORBIDDEN,
+            description: "That calendar feed is invalid or has been replaced.".to_string(),
+            details: None,
+        })?;
+    request.extensions_mut().insert(Arc::new(feed));
+
+    Ok(next.run(request).await)
+}
+
 pub async fn try_decode_session(
     token: String,
     state: &Arc<ServerState>,
@@ -151,6 +261,7 @@ pub async fn try_decode_session(
             Err(_) => Err(AppError {
                 status_code: StatusCode::FORBIDDEN,
                 description: "Invalid session token.".to_string(),
+                details: None,
             }),
         },
     }
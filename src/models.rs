@@ -1,5 +1,7 @@
 use diesel::prelude::*;
 
+use crate::grade_map::GradeMap;
+use crate::scaling::ScalingRule;
 use serde::Serialize;
 use time::OffsetDateTime;
 
@@ -9,7 +11,7 @@ use time::OffsetDateTime;
 #[serde(rename_all = "camelCase")]
 pub struct User {
     pub id: String,
-    pub grade_map: serde_json::Value,
+    pub grade_map: GradeMap,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
 }
@@ -44,6 +46,23 @@ pub struct Course {
     #[serde(rename = "studyBlockId")]
     pub block_id: String,
     pub color: String,
+    /// The course or template this course was originally cloned from, if any.
+    pub origin_id: Option<String>,
+    /// Whether the user has opted in to contributing this course's grades, anonymously,
+    /// to the grade distribution of every course cloned from the same origin.
+    pub share_grade_distribution: bool,
+    /// The grading scale whose grade map overrides the user's own grade map for this course.
+    pub grading_scale_id: Option<String>,
+    /// The number of credits (or points) the course is worth, used to weight it when computing GPA.
+    pub credits: bigdecimal::BigDecimal,
+    /// The minimum overall grade needed to pass the course, if it has one.
+    pub hurdle: Option<bigdecimal::BigDecimal>,
+    /// The overall grade the user is aiming for.
+    pub target: Option<bigdecimal::BigDecimal>,
+    /// The grade the target was set as, if it was set as a grade rather than a percentage.
+    /// Course responses resolve `target` from this grade's current threshold, falling back to
+    /// the threshold at the time it was set if the grade no longer exists.
+    pub target_grade: Option<String>,
 }
 #[derive(
     Queryable, Selectable, Serialize, Associations, Insertable, Identifiable, Clone, Debug,
@@ -61,6 +80,23 @@ pub struct CourseComponent {
     pub course_id: String,
     pub subject_weighting: bigdecimal::BigDecimal,
     pub sequence_number: Option<i16>,
+    /// The minimum grade needed on this component to pass the course, regardless of the
+    /// overall grade.
+    pub hurdle: Option<bigdecimal::BigDecimal>,
+    /// Bonus components aren't counted towards the 100% that a course's weightings must add
+    /// up to, and add at most their weighting on top of the rest of the course.
+    pub is_bonus: bool,
+    /// The highest score a subcomponent can have. Above 1 if the component offers extra credit.
+    pub maximum_score: bigdecimal::BigDecimal,
+    /// The fraction of full marks deducted from a subcomponent for each day (or part of a day)
+    /// it's submitted late.
+    pub late_penalty_per_day: Option<bigdecimal::BigDecimal>,
+    /// The most that can be deducted from a subcomponent for being late, if there's a limit.
+    pub late_penalty_cap: Option<bigdecimal::BigDecimal>,
+    /// Scales the raw score of every subcomponent when grading, if `is_scaling_enabled`.
+    /// Raw scores are left untouched, so that scaling can be turned off again.
+    pub scaling: Option<ScalingRule>,
+    pub is_scaling_enabled: bool,
 }
 
 #[derive(
@@ -77,4 +113,140 @@ pub struct CourseSubcomponent {
     pub is_completed: bool,
     pub number_in_sequence: i32,
     pub override_name: Option<String>,
+    /// The marks awarded, if the score was entered as points out of `points_possible`.
+    /// `grade_value_percentage` is always derived from these when they're set.
+    pub points_earned: Option<bigdecimal::BigDecimal>,
+    pub points_possible: Option<bigdecimal::BigDecimal>,
+    /// The share of the component this subcomponent is worth. Either every subcomponent in a
+    /// component has a weighting and they add up to 1, or none do and they're weighted equally.
+    pub weighting: Option<bigdecimal::BigDecimal>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub due_at: Option<OffsetDateTime>,
+    /// When the subcomponent was submitted, which is compared against its due date
+    /// (or extension) to apply the component's late penalty.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub submitted_at: Option<OffsetDateTime>,
+    /// The due date after an extension, which replaces `due_at` when applying late penalties.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub extended_due_at: Option<OffsetDateTime>,
+}
+
+#[derive(
+    Queryable, Selectable, Serialize, Associations, Insertable, Identifiable, Clone, Debug,
+)]
+#[diesel(table_name = crate::schema::course_share)]
+#[serde(rename_all = "camelCase")]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(primary_key(code))]
+#[diesel(belongs_to(Course))]
+pub struct CourseShare {
+    pub code: String,
+    pub course_id: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+#[derive(Queryable, Selectable, Serialize, Insertable, Identifiable, Clone, Debug)]
+#[diesel(table_name = crate::schema::grading_scale)]
+#[serde(rename_all = "camelCase")]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct GradingScale {
+    pub id: String,
+    /// The user who created this scale, or `None` for the preset scales available to everyone.
+    pub user_id: Option<String>,
+    pub name: String,
+    pub grade_map: GradeMap,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(
+    Queryable, Selectable, Serialize, Associations, Insertable, Identifiable, Clone, Debug,
+)]
+#[diesel(table_name = crate::schema::course_template)]
+#[serde(rename_all = "camelCase")]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(belongs_to(User))]
+pub struct CourseTemplate {
+    pub id: String,
+    #[serde(skip_serializing)]
+    pub user_id: String,
+    pub name: String,
+    pub institution: String,
+    pub long_name: Option<String>,
+    pub course_code_name: Option<String>,
+    pub course_code_number: Option<String>,
+    pub color: String,
+    pub structure: serde_json::Value,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(
+    Queryable, Selectable, Serialize, Associations, Insertable, Identifiable, Clone, Debug,
+)]
+#[diesel(table_name = crate::schema::scenario)]
+#[serde(rename_all = "camelCase")]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(belongs_to(Course))]
+pub struct Scenario {
+    pub id: String,
+    pub course_id: String,
+    pub name: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(
+    Queryable, Selectable, Serialize, Associations, Insertable, Identifiable, Clone, Debug,
+)]
+#[diesel(table_name = crate::schema::scenario_subcomponent)]
+#[serde(rename_all = "camelCase")]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(primary_key(scenario_id, subcomponent_id))]
+#[diesel(belongs_to(Scenario))]
+pub struct ScenarioSubcomponent {
+    pub scenario_id: String,
+    pub subcomponent_id: String,
+    pub grade_value_percentage: bigdecimal::BigDecimal,
+}
+
+#[derive(
+    Queryable, Selectable, Serialize, Associations, Insertable, Identifiable, Clone, Debug,
+)]
+#[diesel(table_name = crate::schema::view_grant)]
+#[serde(rename_all = "camelCase")]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(belongs_to(User))]
+pub struct ViewGrant {
+    pub id: String,
+    pub token: String,
+    #[serde(skip_serializing)]
+    pub user_id: String,
+    #[serde(rename = "studyBlockId")]
+    pub block_id: String,
+    pub course_id: Option<String>,
+    pub label: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+#[derive(
+    Queryable, Selectable, Serialize, Associations, Insertable, Identifiable, Clone, Debug,
+)]
+#[diesel(table_name = crate::schema::calendar_feed)]
+#[serde(rename_all = "camelCase")]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(primary_key(user_id))]
+#[diesel(belongs_to(User))]
+pub struct CalendarFeed {
+    #[serde(skip_serializing)]
+    pub user_id: String,
+    pub token: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
 }
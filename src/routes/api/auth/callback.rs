@@ -62,6 +62,7 @@ pub async fn handle_auth_callback(
         return Err(AppError {
             status_code: StatusCode::UNAUTHORIZED,
             description: "Failed to authorize with Google.".to_string(),
+            details: None,
         });
     };
 
@@ -114,6 +115,7 @@ pub async fn handle_auth_callback(
         return Err(AppError {
             status_code: StatusCode::BAD_REQUEST,
             description: "You have not verified your email with Google.".to_string(),
+            details: None,
         });
     };
 
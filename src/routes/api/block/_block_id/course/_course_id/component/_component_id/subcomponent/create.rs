@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use bigdecimal::{BigDecimal, Zero};
+use cuid2::cuid;
+use diesel::{insert_into, Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::CourseSubcomponent;
+use crate::routes::api::block::_block_id::course::_course_id::component::_component_id::subcomponent::{
+    has_subcomponent_weightings, load_component, renumber_subcomponents,
+};
+use crate::routes::api::users::me::GetUserComponent;
+use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubcomponent {
+    pub override_name: Option<String>,
+    /// Position to insert the new subcomponent at. Appended to the end if not provided.
+    pub number_in_sequence: Option<i32>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub due_at: Option<OffsetDateTime>,
+}
+
+pub async fn create_subcomponent(
+    Path((_block_id, _course_id, _component_id)): Path<(String, String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(subcomponent_data): Json<CreateSubcomponent>,
+) -> AppResult<Json<GetUserComponent>> {
+    let con = &mut state.get_db_con()?;
+
+    con.transaction(|txn| {
+        let subcomponents_in_course: i64 = course_subcomponent
+            .inner_join(course_component)
+            .filter(crate::schema::course_component::course_id.eq(&_course_id))
+            .count()
+            .get_result(txn)?;
+        if subcomponents_in_course >= 100 {
+            return AppError::bad_request("Total number of subcomponents must not exceed 100.")
+                .into();
+        }
+
+        // New subcomponents in a weighted component start off worth nothing, so that the
+        // component still adds up to 100% until the user sets their weightings.
+        let new_weighting =
+            has_subcomponent_weightings(txn, &_component_id)?.then(BigDecimal::zero);
+        let new_subcomponent = CourseSubcomponent {
+            id: cuid(),
+            component_id: _component_id.clone(),
+            grade_value_percentage: BigDecimal::zero(),
+            is_completed: false,
+            number_in_sequence: i32::MAX,
+            override_name: subcomponent_data.override_name,
+            points_earned: None,
+            points_possible: None,
+            weighting: new_weighting,
+            due_at: subcomponent_data.due_at,
+            submitted_at: None,
+            extended_due_at: None,
+        };
+        insert_into(course_subcomponent)
+            .values(&new_subcomponent)
+            .execute(txn)?;
+        renumber_subcomponents(
+            txn,
+            &_component_id,
+            subcomponent_data
+                .number_in_sequence
+                .map(|position| (new_subcomponent.id.as_str(), position)),
+        )?;
+
+        Ok(Json(load_component(txn, &_component_id)?))
+    })
+}
@@ -0,0 +1,284 @@
+use bigdecimal::{BigDecimal, One, RoundingMode, Zero};
+use diesel::{
+    update, AsChangeset, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+
+use serde::{Deserialize, Deserializer};
+use time::OffsetDateTime;
+
+use crate::errors::{AppError, AppResult};
+use crate::grading::round_grade;
+use crate::models::{CourseComponent, CourseSubcomponent};
+use crate::routes::api::users::me::GetUserComponent;
+use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::schema::course_subcomponent::{component_id, id, number_in_sequence, weighting};
+
+pub(crate) mod create;
+pub(crate) mod subcomponent_id;
+
+/// Deserializes an optional date, such as a due date, so that a missing date (`None`) can be
+/// told apart from a `null` one (`Some(None)`), which removes the date.
+pub(crate) fn deserialize_date_time<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Option<OffsetDateTime>>, D::Error> {
+    time::serde::rfc3339::option::deserialize(deserializer).map(Some)
+}
+
+/// Deserializes an optional weighting, so that a missing weighting (`None`) can be told apart
+/// from a `null` one (`Some(None)`), which removes the weighting.
+pub(crate) fn deserialize_weighting<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Option<BigDecimal>>, D::Error> {
+    Option::<BigDecimal>::deserialize(deserializer).map(Some)
+}
+
+/// The score of a subcomponent, which can be given either as a percentage, or as points
+/// out of a maximum that the percentage is derived from.
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::course_subcomponent)]
+#[diesel(treat_none_as_null = true)]
+pub(crate) struct SubcomponentScore {
+    grade_value_percentage: BigDecimal,
+    points_earned: Option<BigDecimal>,
+    points_possible: Option<BigDecimal>,
+}
+
+impl SubcomponentScore {
+    /// Validates a score given in either form, or returns `None` if no score was given.
+    /// Points take precedence over a percentage if both are given.
+    /// Scores can't be higher than the `maximum_score` of the subcomponent's component.
+    pub(crate) fn resolve(
+        grade_value_percentage: Option<BigDecimal>,
+        points_earned: Option<BigDecimal>,
+        points_possible: Option<BigDecimal>,
+        maximum_score: &BigDecimal,
+    ) -> AppResult<Option<SubcomponentScore>> {
+        let score = match (points_earned, points_possible) {
+            (Some(earned), Some(possible)) => {
+                if possible <= BigDecimal::zero() {
+                    return AppError::bad_request("Points possible must be greater than zero.")
+                        .into();
+                }
+                if possible >= BigDecimal::new(1.into(), -6) {
+                    return AppError::bad_request("Points possible must be less than 1000000.")
+                        .into();
+                }
+                if earned >= BigDecimal::new(1.into(), -6) {
+                    return AppError::bad_request("Points earned must be less than 1000000.")
+                        .into();
+                }
+                SubcomponentScore {
+                    grade_value_percentage: round_grade(&(&earned / &possible)),
+                    points_earned: Some(earned),
+                    points_possible: Some(possible),
+                }
+            }
+            (None, None) => match grade_value_percentage {
+                Some(percentage) => SubcomponentScore {
+                    grade_value_percentage: percentage,
+                    points_earned: None,
+                    points_possible: None,
+                },
+                None => return Ok(None),
+            },
+            _ => {
+                return AppError::bad_request(
+                    "Points earned and points possible must be given together.",
+                )
+                .into()
+            }
+        };
+
+        if score.grade_value_percentage > *maximum_score {
+            return AppError::bad_request(format!(
+                "Can't set a score higher than {}%.",
+                (maximum_score * BigDecimal::from(100)).normalized()
+            ))
+            .into();
+        }
+        if score.grade_value_percentage < BigDecimal::zero() {
+            return AppError::bad_request("Can't set a score lower than zero.").into();
+        }
+        Ok(Some(score))
+    }
+
+    /// Stores the score. A percentage given without points only replaces the stored points if
+    /// it differs from the stored percentage, so clients that don't know about points don't
+    /// erase them every time they save.
+    pub(crate) fn apply(&self, con: &mut PgConnection, _subcomponent_id: &str) -> AppResult<()> {
+        if self.points_earned.is_none() {
+            let stored_percentage: BigDecimal = course_subcomponent
+                .filter(id.eq(_subcomponent_id))
+                .select(crate::schema::course_subcomponent::grade_value_percentage)
+                .get_result(con)?;
+            if stored_percentage == self.grade_value_percentage {
+                return Ok(());
+            }
+        }
+        update(course_subcomponent.filter(id.eq(_subcomponent_id)))
+            .set(self)
+            .execute(con)?;
+        Ok(())
+    }
+}
+
+/// Rewrites `number_in_sequence` for every subcomponent in a component so that they run
+/// contiguously from 1, optionally moving one subcomponent to a new position first.
+pub(crate) fn renumber_subcomponents(
+    con: &mut PgConnection,
+    _component_id: &str,
+    moved: Option<(&str, i32)>,
+) -> AppResult<()> {
+    let mut subcomponents: Vec<CourseSubcomponent> = course_subcomponent
+        .filter(component_id.eq(_component_id))
+        .order((number_in_sequence.asc(), id.asc()))
+        .select(CourseSubcomponent::as_select())
+        .load(con)?;
+
+    if let Some((moved_id, position)) = moved {
+        if let Some(index) = subcomponents.iter().position(|s| s.id == moved_id) {
+            let subcomponent = subcomponents.remove(index);
+            let new_index = (position - 1).clamp(0, subcomponents.len() as i32) as usize;
+            subcomponents.insert(new_index, subcomponent);
+        }
+    }
+
+    for (i, subcomponent) in subcomponents.iter().enumerate() {
+        let sequence = (i + 1) as i32;
+        if subcomponent.number_in_sequence != sequence {
+            update(course_subcomponent.filter(id.eq(&subcomponent.id)))
+                .set(number_in_sequence.eq(sequence))
+                .execute(con)?;
+        }
+    }
+    Ok(())
+}
+
+/// Ensures that either none of the subcomponents in a component have a weighting,
+/// or all of them do and they add up to 100% of the component.
+pub(crate) fn validate_subcomponent_weighting(
+    con: &mut PgConnection,
+    _component_id: &str,
+) -> AppResult<()> {
+    let weightings: Vec<Option<BigDecimal>> = course_subcomponent
+        .filter(component_id.eq(_component_id))
+        .select(weighting)
+        .load(con)?;
+    if weightings.iter().all(Option::is_none) {
+        return Ok(());
+    }
+    if weightings.iter().any(Option::is_none) {
+        return AppError::bad_request(
+            "Either every subcomponent in a component must have a weighting, or none of them.",
+        )
+        .into();
+    }
+
+    let total = weightings.into_iter().flatten().sum::<BigDecimal>();
+    if total != BigDecimal::one() {
+        return Err(AppError::invalid_subcomponent_weighting(total));
+    }
+    Ok(())
+}
+
+/// Whether the subcomponents in a component have their own weightings.
+pub(crate) fn has_subcomponent_weightings(
+    con: &mut PgConnection,
+    _component_id: &str,
+) -> AppResult<bool> {
+    let weighted: i64 = course_subcomponent
+        .filter(component_id.eq(_component_id))
+        .filter(weighting.is_not_null())
+        .count()
+        .get_result(con)?;
+    Ok(weighted > 0)
+}
+
+/// Scales the weightings of the subcomponents in a component proportionally so that they
+/// add up to 100% again, after a subcomponent has been removed.
+pub(crate) fn rebalance_subcomponent_weightings(
+    con: &mut PgConnection,
+    _component_id: &str,
+) -> AppResult<()> {
+    let weightings = course_subcomponent
+        .filter(component_id.eq(_component_id))
+        .filter(weighting.is_not_null())
+        .select((id, weighting))
+        .load::<(String, Option<BigDecimal>)>(con)?
+        .into_iter()
+        .map(|(subcomponent_id, w)| (subcomponent_id, w.unwrap_or_else(BigDecimal::zero)))
+        .collect::<Vec<(String, BigDecimal)>>();
+    if weightings.is_empty() {
+        return Ok(());
+    }
+
+    let total = weightings.iter().map(|(_, w)| w).sum::<BigDecimal>();
+    let count = BigDecimal::from(weightings.len() as i64);
+
+    // Weightings are stored as numeric(5,4), so round every new weighting down to that scale
+    // and give whatever is left over to the largest subcomponent.
+    let mut new_weightings = weightings
+        .into_iter()
+        .map(|(subcomponent_id, w)| {
+            let scaled = match total.is_zero() {
+                true => BigDecimal::one() / &count,
+                false => w / &total,
+            };
+            (
+                subcomponent_id,
+                scaled.with_scale_round(4, RoundingMode::Down),
+            )
+        })
+        .collect::<Vec<(String, BigDecimal)>>();
+    let residual = BigDecimal::one() - new_weightings.iter().map(|(_, w)| w).sum::<BigDecimal>();
+    if let Some((_, largest)) = new_weightings.iter_mut().max_by(|(_, a), (_, b)| a.cmp(b)) {
+        *largest += residual;
+    }
+
+    for (subcomponent_id, new_weighting) in new_weightings {
+        update(course_subcomponent.filter(id.eq(&subcomponent_id)))
+            .set(weighting.eq(new_weighting))
+            .execute(con)?;
+    }
+    Ok(())
+}
+
+/// Ensures that no subcomponent in a component scores more than the component's maximum score.
+pub(crate) fn validate_maximum_scores(
+    con: &mut PgConnection,
+    _component_id: &str,
+) -> AppResult<()> {
+    let maximum_score: BigDecimal = course_component
+        .filter(crate::schema::course_component::id.eq(_component_id))
+        .select(crate::schema::course_component::maximum_score)
+        .get_result(con)?;
+    let over_maximum: i64 = course_subcomponent
+        .filter(component_id.eq(_component_id))
+        .filter(crate::schema::course_subcomponent::grade_value_percentage.gt(&maximum_score))
+        .count()
+        .get_result(con)?;
+    if over_maximum > 0 {
+        return AppError::bad_request(
+            "Can't lower the maximum score below a score that's already been set.",
+        )
+        .into();
+    }
+    Ok(())
+}
+
+pub(crate) fn load_component(
+    con: &mut PgConnection,
+    _component_id: &str,
+) -> AppResult<GetUserComponent> {
+    let component = course_component
+        .filter(crate::schema::course_component::id.eq(_component_id))
+        .select(CourseComponent::as_select())
+        .get_result(con)?;
+    let subcomponents = course_subcomponent
+        .filter(component_id.eq(_component_id))
+        .order(number_in_sequence.asc())
+        .select(CourseSubcomponent::as_select())
+        .load(con)?;
+    Ok(GetUserComponent::new(component, subcomponents))
+}
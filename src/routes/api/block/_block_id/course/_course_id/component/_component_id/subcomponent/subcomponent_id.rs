@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use bigdecimal::BigDecimal;
+use diesel::result::Error::QueryBuilderError;
+use diesel::{delete, update, AsChangeset, Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::errors::{AppError, AppResult};
+use crate::routes::api::block::_block_id::course::_course_id::component::_component_id::subcomponent::{
+    deserialize_date_time, load_component, rebalance_subcomponent_weightings, renumber_subcomponents,
+    SubcomponentScore,
+};
+use crate::routes::api::users::me::GetUserComponent;
+use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::schema::course_subcomponent::id;
+use crate::ServerState;
+
+#[derive(Deserialize, AsChangeset)]
+#[serde(rename_all = "camelCase")]
+#[diesel(table_name=crate::schema::course_subcomponent)]
+pub struct UpdateSubcomponent {
+    pub override_name: Option<String>,
+    pub is_completed: Option<bool>,
+    #[diesel(skip_update)]
+    pub grade_value_percentage: Option<BigDecimal>,
+    #[diesel(skip_update)]
+    pub points_earned: Option<BigDecimal>,
+    #[diesel(skip_update)]
+    pub points_possible: Option<BigDecimal>,
+    #[diesel(skip_update)]
+    pub number_in_sequence: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_date_time")]
+    pub due_at: Option<Option<OffsetDateTime>>,
+    #[serde(default, deserialize_with = "deserialize_date_time")]
+    pub submitted_at: Option<Option<OffsetDateTime>>,
+    #[serde(default, deserialize_with = "deserialize_date_time")]
+    pub extended_due_at: Option<Option<OffsetDateTime>>,
+}
+
+pub async fn update_subcomponent(
+    Path((_block_id, _course_id, _component_id, _subcomponent_id)): Path<(
+        String,
+        String,
+        String,
+        String,
+    )>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(subcomponent_data): Json<UpdateSubcomponent>,
+) -> AppResult<Json<GetUserComponent>> {
+    let con = &mut state.get_db_con()?;
+
+    let maximum_score: BigDecimal = course_component
+        .find(&_component_id)
+        .select(crate::schema::course_component::maximum_score)
+        .get_result(con)?;
+    let score = SubcomponentScore::resolve(
+        subcomponent_data.grade_value_percentage.clone(),
+        subcomponent_data.points_earned.clone(),
+        subcomponent_data.points_possible.clone(),
+        &maximum_score,
+    )?;
+
+    con.transaction(|txn| {
+        match update(course_subcomponent.filter(id.eq(&_subcomponent_id)))
+            .set(&subcomponent_data)
+            .execute(txn)
+        {
+            Ok(_) | Err(QueryBuilderError(_)) => {}
+            Err(e) => return Err(AppError::database_ise(e)),
+        }
+        if let Some(score) = &score {
+            score.apply(txn, &_subcomponent_id)?;
+        }
+        if let Some(position) = subcomponent_data.number_in_sequence {
+            renumber_subcomponents(txn, &_component_id, Some((&_subcomponent_id, position)))?;
+        }
+
+        Ok(Json(load_component(txn, &_component_id)?))
+    })
+}
+
+pub async fn delete_subcomponent(
+    Path((_block_id, _course_id, _component_id, _subcomponent_id)): Path<(
+        String,
+        String,
+        String,
+        String,
+    )>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<GetUserComponent>> {
+    let con = &mut state.get_db_con()?;
+
+    con.transaction(|txn| {
+        let result = delete(course_subcomponent.filter(id.eq(&_subcomponent_id))).execute(txn)?;
+        if result != 1 {
+            return Err(AppError::resource_not_found());
+        }
+        renumber_subcomponents(txn, &_component_id, None)?;
+        rebalance_subcomponent_weightings(txn, &_component_id)?;
+
+        Ok(Json(load_component(txn, &_component_id)?))
+    })
+}
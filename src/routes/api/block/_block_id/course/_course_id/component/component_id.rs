@@ -1,20 +1,35 @@
 use axum::extract::Path;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::{Extension, Json};
 use bigdecimal::{BigDecimal, One, Zero};
 
 use diesel::{
-    update, AsChangeset, BelongingToDsl, Connection, ExpressionMethods, QueryDsl, RunQueryDsl,
-    SelectableHelper,
+    delete, update, AsChangeset, BelongingToDsl, Connection, ExpressionMethods, QueryDsl,
+    RunQueryDsl, SelectableHelper,
 };
 
-use crate::errors::AppError;
+use crate::errors::{AppError, AppResult};
 use crate::models::{CourseComponent, CourseSubcomponent};
 use diesel::result::Error::QueryBuilderError;
 use serde::Deserialize;
+use time::OffsetDateTime;
 
-use crate::routes::api::users::me::GetUserComponent;
+use crate::routes::api::block::_block_id::course::_course_id::component::_component_id::subcomponent::{
+    deserialize_date_time, deserialize_weighting, validate_maximum_scores, validate_subcomponent_weighting,
+    SubcomponentScore,
+};
+use crate::routes::api::block::_block_id::course::_course_id::component::{
+    apply_component_weightings, deserialize_scaling, rebalance_component_weightings,
+    renumber_components, validate_course_weighting,
+};
+use crate::routes::api::block::_block_id::course::course_id::get_course;
+use crate::routes::api::block::_block_id::course::create::{
+    validate_bonus_weighting, validate_component_weighting, validate_hurdle, validate_late_penalty, validate_maximum_score,
+};
+use crate::routes::api::users::me::{GetUserComponent, GetUserCourse};
+use crate::scaling::ScalingRule;
 use crate::schema::course_component::dsl::course_component;
 use crate::schema::course_component::id;
 
@@ -30,6 +45,21 @@ pub struct UpdateCourseComponentChangeset {
     pub subject_weighting: Option<BigDecimal>,
     #[serde(rename = "numberOfSubComponentsToDrop_Lowest")]
     pub number_of_subcomponents_to_drop_lowest: Option<i32>,
+    /// A hurdle of zero removes the component's hurdle.
+    #[diesel(skip_update)]
+    pub hurdle: Option<BigDecimal>,
+    pub is_bonus: Option<bool>,
+    pub maximum_score: Option<BigDecimal>,
+    /// A late penalty of zero stops penalising late submissions.
+    #[diesel(skip_update)]
+    pub late_penalty_per_day: Option<BigDecimal>,
+    /// A late penalty cap of zero removes the cap.
+    #[diesel(skip_update)]
+    pub late_penalty_cap: Option<BigDecimal>,
+    #[serde(default, deserialize_with = "deserialize_scaling")]
+    pub scaling: Option<Option<ScalingRule>>,
+    /// Turns scaling off or back on, without losing the scaling rule.
+    pub is_scaling_enabled: Option<bool>,
 }
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -37,6 +67,14 @@ pub struct UpdateCourseComponent {
     #[serde(flatten)]
     pub changeset: UpdateCourseComponentChangeset,
     pub subcomponents: Option<Vec<UpdateCourseSubcomponentChangeset>>,
+    /// If the weighting changes, scale every other component in the course proportionally
+    /// so that the course still adds up to 100%, rather than rejecting the update.
+    #[serde(default)]
+    pub rebalance: bool,
+    /// New weightings for other components in the course, so that the course still adds up
+    /// to 100% after the weighting changes.
+    #[serde(default)]
+    pub weightings: HashMap<String, BigDecimal>,
 }
 
 #[derive(Deserialize, AsChangeset)]
@@ -48,7 +86,22 @@ pub struct UpdateCourseSubcomponentChangeset {
     number_in_sequence: i32,
     override_name: Option<String>,
     is_completed: bool,
-    grade_value_percentage: BigDecimal,
+    #[diesel(skip_update)]
+    grade_value_percentage: Option<BigDecimal>,
+    #[diesel(skip_update)]
+    points_earned: Option<BigDecimal>,
+    #[diesel(skip_update)]
+    points_possible: Option<BigDecimal>,
+    /// A `null` weighting removes it, so removing it from every subcomponent in the component
+    /// goes back to weighting them equally.
+    #[serde(default, deserialize_with = "deserialize_weighting")]
+    weighting: Option<Option<BigDecimal>>,
+    #[serde(default, deserialize_with = "deserialize_date_time")]
+    due_at: Option<Option<OffsetDateTime>>,
+    #[serde(default, deserialize_with = "deserialize_date_time")]
+    submitted_at: Option<Option<OffsetDateTime>>,
+    #[serde(default, deserialize_with = "deserialize_date_time")]
+    extended_due_at: Option<Option<OffsetDateTime>>,
 }
 
 pub async fn update_course_component(
@@ -58,33 +111,94 @@ pub async fn update_course_component(
 ) -> Result<Json<GetUserComponent>, AppError> {
     let con = &mut state.get_db_con()?;
 
+    if let Some(weighting) = &_component_data.changeset.subject_weighting {
+        validate_component_weighting(weighting)?;
+    }
+
+    if let Some(maximum_score) = &_component_data.changeset.maximum_score {
+        validate_maximum_score(maximum_score)?;
+    }
+    if let Some(Some(scaling)) = &_component_data.changeset.scaling {
+        scaling.validate().map_err(AppError::bad_request)?;
+    }
+
+    let new_hurdle = _component_data
+        .changeset
+        .hurdle
+        .clone()
+        .map(|h| validate_hurdle(Some(h)))
+        .transpose()?;
+    let new_late_penalty_per_day = _component_data
+        .changeset
+        .late_penalty_per_day
+        .clone()
+        .map(|p| validate_late_penalty(Some(p)))
+        .transpose()?;
+    let new_late_penalty_cap = _component_data
+        .changeset
+        .late_penalty_cap
+        .clone()
+        .map(|p| validate_late_penalty(Some(p)))
+        .transpose()?;
+
     con.transaction(|txn| {
+        if let Some(new_hurdle) = new_hurdle {
+            update(course_component.filter(id.eq(&_component_id)))
+                .set(schema::course_component::hurdle.eq(new_hurdle))
+                .execute(txn)?;
+        }
+        if let Some(new_late_penalty_per_day) = new_late_penalty_per_day {
+            update(course_component.filter(id.eq(&_component_id)))
+                .set(schema::course_component::late_penalty_per_day.eq(new_late_penalty_per_day))
+                .execute(txn)?;
+        }
+        if let Some(new_late_penalty_cap) = new_late_penalty_cap {
+            update(course_component.filter(id.eq(&_component_id)))
+                .set(schema::course_component::late_penalty_cap.eq(new_late_penalty_cap))
+                .execute(txn)?;
+        }
+
         match _component_data.subcomponents {
             None => {}
             Some(new_subcomponents) => {
+                let maximum_score = match &_component_data.changeset.maximum_score {
+                    Some(maximum_score) => maximum_score.clone(),
+                    None => course_component
+                        .filter(id.eq(&_component_id))
+                        .select(schema::course_component::maximum_score)
+                        .get_result(txn)?,
+                };
                 for new_subcomponent in new_subcomponents {
                     if new_subcomponent.component_id != _component_id {
                         return Err(AppError::resource_access_denied());
                     }
                     if new_subcomponent
-                        .grade_value_percentage
-                        .gt(&BigDecimal::one())
-                    {
-                        return Err(AppError::bad_request("Can't set a score higher than 100%."));
-                    }
-                    if new_subcomponent
-                        .grade_value_percentage
-                        .lt(&BigDecimal::zero())
+                        .weighting
+                        .as_ref()
+                        .and_then(Option::as_ref)
+                        .is_some_and(|w| w.lt(&BigDecimal::zero()) || w.gt(&BigDecimal::one()))
                     {
-                        return Err(AppError::bad_request("Can't set a score lower than zero."));
+                        return Err(AppError::bad_request(
+                            "Subcomponent weighting must be between 0% and 100%.",
+                        ));
                     }
+                    let score = SubcomponentScore::resolve(
+                        new_subcomponent.grade_value_percentage.clone(),
+                        new_subcomponent.points_earned.clone(),
+                        new_subcomponent.points_possible.clone(),
+                        &maximum_score,
+                    )?;
                     update(
                         course_subcomponent
                             .filter(schema::course_subcomponent::id.eq(&new_subcomponent.id)),
                     )
                     .set(&new_subcomponent)
                     .execute(txn)?;
+                    if let Some(score) = &score {
+                        score.apply(txn, &new_subcomponent.id)?;
+                    }
                 }
+                validate_subcomponent_weighting(txn, &_component_id)?;
             }
         }
 
@@ -93,6 +207,31 @@ pub async fn update_course_component(
             .execute(txn)
         {
             Ok(_) | Err(QueryBuilderError(_)) => {
+                if _component_data.changeset.maximum_score.is_some() {
+                    validate_maximum_scores(txn, &_component_id)?;
+                }
+                let reweighted = !_component_data.weightings.is_empty();
+                apply_component_weightings(txn, &_course_id, _component_data.weightings)?;
+                if _component_data.changeset.subject_weighting.is_some()
+                    || _component_data.changeset.is_bonus.is_some()
+                    || reweighted
+                {
+                    let (bonus, weighting): (bool, BigDecimal) = course_component
+                        .filter(id.eq(&_component_id))
+                        .select((
+                            schema::course_component::is_bonus,
+                            schema::course_component::subject_weighting,
+                        ))
+                        .get_result(txn)?;
+                    if bonus {
+                        validate_bonus_weighting(&weighting)?;
+                    }
+                    if _component_data.rebalance {
+                        rebalance_component_weightings(txn, &_course_id, Some(&_component_id))?;
+                    }
+                    validate_course_weighting(txn, &_course_id)?;
+                }
+
                 let component = course_component
                     .filter(id.eq(&_component_id))
                     .select(CourseComponent::as_select())
@@ -101,12 +240,47 @@ pub async fn update_course_component(
                     .select(CourseSubcomponent::as_select())
                     .get_results(txn)?;
 
-                Ok(Json(GetUserComponent {
-                    component,
-                    subcomponents,
-                }))
+                Ok(Json(GetUserComponent::new(component, subcomponents)))
             }
             Err(e) => Err(AppError::database_ise(e)),
         }
     })
 }
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteCourseComponent {
+    /// New weightings for the remaining components, so that the course still adds up to 100%.
+    #[serde(default)]
+    pub weightings: HashMap<String, BigDecimal>,
+    /// Scale the remaining components proportionally so that the course still adds up to 100%,
+    /// rather than requiring new weightings.
+    #[serde(default)]
+    pub rebalance: bool,
+}
+
+/// Deletes a component. The body is optional, since deleting a bonus component (or one worth
+/// nothing) doesn't need any new weightings.
+pub async fn delete_course_component(
+    Path((_block_id, _course_id, _component_id)): Path<(String, String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+    delete_data: Option<Json<DeleteCourseComponent>>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+    let _delete_data = delete_data.map(|Json(d)| d).unwrap_or_default();
+
+    con.transaction(|txn| {
+        let result = delete(course_component.filter(id.eq(&_component_id))).execute(txn)?;
+        if result != 1 {
+            return Err(AppError::resource_not_found());
+        }
+        apply_component_weightings(txn, &_course_id, _delete_data.weightings)?;
+        if _delete_data.rebalance {
+            rebalance_component_weightings(txn, &_course_id, None)?;
+        }
+        validate_course_weighting(txn, &_course_id)?;
+        renumber_components(txn, &_course_id)
+    })?;
+
+    get_course(Path((_block_id, _course_id)), Extension(state)).await
+}
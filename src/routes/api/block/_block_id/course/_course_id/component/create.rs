@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use bigdecimal::{BigDecimal, One, Zero};
+use cuid2::cuid;
+use diesel::{insert_into, Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use serde::Deserialize;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{CourseComponent, CourseSubcomponent};
+use crate::routes::api::block::_block_id::course::_course_id::component::{
+    apply_component_weightings, validate_course_weighting,
+};
+use crate::routes::api::block::_block_id::course::course_id::get_course;
+use crate::routes::api::block::_block_id::course::create::{
+    validate_bonus_weighting, validate_component_weighting, validate_hurdle, validate_late_penalty,
+    validate_maximum_score, CreateCourseComponent,
+};
+use crate::routes::api::users::me::GetUserCourse;
+use crate::schema::course_component::course_id;
+use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateComponent {
+    #[serde(flatten)]
+    pub component: CreateCourseComponent,
+    /// New weightings for existing components, so that the course still adds up to 100%.
+    #[serde(default)]
+    pub weightings: HashMap<String, BigDecimal>,
+}
+
+pub async fn create_course_component(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(component_data): Json<CreateComponent>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+
+    let Ok(n_subc) = component_data
+        .component
+        .number_of_subcomponents
+        .parse::<i32>()
+    else {
+        return AppError::bad_request("Number of subcomponents must be a number.").into();
+    };
+
+    match component_data.component.is_bonus {
+        true => validate_bonus_weighting(&component_data.component.weighting)?,
+        false => validate_component_weighting(&component_data.component.weighting)?,
+    }
+    let new_hurdle = validate_hurdle(component_data.component.hurdle)?;
+    let late_penalty_per_day =
+        validate_late_penalty(component_data.component.late_penalty_per_day)?;
+    let late_penalty_cap = validate_late_penalty(component_data.component.late_penalty_cap)?;
+    if let Some(maximum_score) = &component_data.component.maximum_score {
+        validate_maximum_score(maximum_score)?;
+    }
+    if let Some(scaling) = &component_data.component.scaling {
+        scaling.validate().map_err(AppError::bad_request)?;
+    }
+
+    con.transaction(|txn| {
+        let subcomponents_in_course: i64 = course_subcomponent
+            .inner_join(course_component)
+            .filter(course_id.eq(&_course_id))
+            .count()
+            .get_result(txn)?;
+        if subcomponents_in_course + n_subc as i64 > 100 {
+            return AppError::bad_request("Total number of subcomponents must not exceed 100.")
+                .into();
+        }
+        let components_in_course: i64 = course_component
+            .filter(course_id.eq(&_course_id))
+            .count()
+            .get_result(txn)?;
+
+        let new_component_id = cuid();
+        let new_component = CourseComponent {
+            id: new_component_id.clone(),
+            name: component_data.component.name,
+            course_id: _course_id.clone(),
+            subject_weighting: component_data.component.weighting,
+            number_of_subcomponents_to_drop_lowest: component_data.component.drop_lowest,
+            name_of_subcomponent_singular: "".to_string(),
+            sequence_number: Some((components_in_course + 1) as i16),
+            hurdle: new_hurdle,
+            is_bonus: component_data.component.is_bonus,
+            maximum_score: component_data
+                .component
+                .maximum_score
+                .unwrap_or_else(BigDecimal::one),
+            late_penalty_per_day,
+            late_penalty_cap,
+            scaling: component_data.component.scaling,
+            is_scaling_enabled: true,
+        };
+        let new_subcomponents = (1..(n_subc + 1))
+            .map(|i| CourseSubcomponent {
+                id: cuid(),
+                component_id: new_component_id.clone(),
+                grade_value_percentage: BigDecimal::zero(),
+                is_completed: false,
+                number_in_sequence: i,
+                override_name: None,
+                points_earned: None,
+                points_possible: None,
+                weighting: None,
+                due_at: None,
+                submitted_at: None,
+                extended_due_at: None,
+            })
+            .collect::<Vec<CourseSubcomponent>>();
+
+        insert_into(course_component)
+            .values(&new_component)
+            .execute(txn)?;
+        insert_into(course_subcomponent)
+            .values(&new_subcomponents)
+            .execute(txn)?;
+        apply_component_weightings(txn, &_course_id, component_data.weightings)?;
+        validate_course_weighting(txn, &_course_id)
+    })?;
+
+    get_course(Path((_block_id, _course_id)), Extension(state)).await
+}
@@ -1 +1,134 @@
+use std::collections::HashMap;
+
+use bigdecimal::{BigDecimal, One, RoundingMode, Zero};
+use diesel::{update, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::errors::{AppError, AppResult};
+use crate::routes::api::block::_block_id::course::create::{
+    validate_bonus_weighting, validate_component_weighting, validate_weighting_total,
+};
+use crate::scaling::ScalingRule;
+use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_component::{
+    course_id, id, is_bonus, sequence_number, subject_weighting,
+};
+
+pub(crate) mod _component_id;
 pub(crate) mod component_id;
+pub(crate) mod create;
+
+/// Deserializes an optional scaling rule, so that a missing rule (`None`) can be told apart
+/// from a `null` one (`Some(None)`), which removes the rule.
+pub(crate) fn deserialize_scaling<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Option<ScalingRule>>, D::Error> {
+    Option::<ScalingRule>::deserialize(deserializer).map(Some)
+}
+
+/// Sets new weightings on existing components in a course, validating each one against
+/// whether its component is a bonus component.
+pub(crate) fn apply_component_weightings(
+    con: &mut PgConnection,
+    _course_id: &str,
+    weightings: HashMap<String, BigDecimal>,
+) -> AppResult<()> {
+    for (component_id, weighting) in weightings {
+        validate_component_weighting(&weighting)?;
+        match update(course_component)
+            .filter(id.eq(&component_id))
+            .filter(course_id.eq(_course_id))
+            .set(subject_weighting.eq(&weighting))
+            .returning(is_bonus)
+            .get_results::<bool>(con)?
+            .as_slice()
+        {
+            [false] => {}
+            [true] => validate_bonus_weighting(&weighting)?,
+            _ => return Err(AppError::bad_request("You don't own that component.")),
+        }
+    }
+    Ok(())
+}
+
+/// Ensures that the components of a course, as currently stored, add up to 100%.
+/// Bonus components aren't counted.
+pub(crate) fn validate_course_weighting(con: &mut PgConnection, _course_id: &str) -> AppResult<()> {
+    let weightings: Vec<BigDecimal> = course_component
+        .filter(course_id.eq(_course_id))
+        .filter(is_bonus.eq(false))
+        .select(subject_weighting)
+        .load(con)?;
+    validate_weighting_total(&weightings)
+}
+
+/// Rewrites `sequence_number` for every component in a course so that they run
+/// contiguously from 1, preserving their current order.
+pub(crate) fn renumber_components(con: &mut PgConnection, _course_id: &str) -> AppResult<()> {
+    let component_ids: Vec<String> = course_component
+        .filter(course_id.eq(_course_id))
+        .order((sequence_number.asc(), id.asc()))
+        .select(id)
+        .load(con)?;
+    for (i, component_id) in component_ids.iter().enumerate() {
+        update(course_component.filter(id.eq(component_id)))
+            .set(sequence_number.eq((i + 1) as i16))
+            .execute(con)?;
+    }
+    Ok(())
+}
+
+/// Scales the weightings of every other component in a course proportionally, so that the
+/// course adds up to 100% around the current weighting of `fixed_component_id`, or so that
+/// every component together adds up to 100% if there's no fixed component.
+/// Bonus components are never rebalanced, and don't affect any other component.
+pub(crate) fn rebalance_component_weightings(
+    con: &mut PgConnection,
+    _course_id: &str,
+    fixed_component_id: Option<&str>,
+) -> AppResult<()> {
+    let (fixed, others): (Vec<_>, Vec<_>) = course_component
+        .filter(course_id.eq(_course_id))
+        .select((id, subject_weighting, is_bonus))
+        .load::<(String, BigDecimal, bool)>(con)?
+        .into_iter()
+        .partition(|(component_id, _, _)| Some(component_id.as_str()) == fixed_component_id);
+    let fixed_weighting = match (fixed_component_id, fixed.first()) {
+        (None, _) => BigDecimal::zero(),
+        (Some(_), None) => return Err(AppError::resource_not_found()),
+        (Some(_), Some((_, _, true))) => return Ok(()),
+        (Some(_), Some((_, weighting, false))) => weighting.clone(),
+    };
+    let others = others
+        .into_iter()
+        .filter(|(_, _, bonus)| !bonus)
+        .map(|(component_id, weighting, _)| (component_id, weighting))
+        .collect::<Vec<(String, BigDecimal)>>();
+    if others.is_empty() {
+        return Ok(());
+    }
+
+    let remaining = BigDecimal::one() - &fixed_weighting;
+    let others_total = others.iter().map(|(_, w)| w).sum::<BigDecimal>();
+    let others_count = BigDecimal::from(others.len() as i64);
+
+    // Weightings are stored as numeric(5,4), so round every new weighting down to that scale
+    // and give whatever is left over to the largest component.
+    let mut new_weightings = others
+        .into_iter()
+        .map(|(component_id, weighting)| {
+            let scaled = match others_total.is_zero() {
+                true => &remaining / &others_count,
+                false => weighting * &remaining / &others_total,
+            };
+            (component_id, scaled.with_scale_round(4, RoundingMode::Down))
+        })
+        .collect::<Vec<(String, BigDecimal)>>();
+    let residual = &remaining - new_weightings.iter().map(|(_, w)| w).sum::<BigDecimal>();
+    if let Some((_, largest)) = new_weightings.iter_mut().max_by(|(_, a), (_, b)| a.cmp(b)) {
+        *largest += residual;
+    }
+
+    apply_component_weightings(con, _course_id, new_weightings.into_iter().collect())
+}
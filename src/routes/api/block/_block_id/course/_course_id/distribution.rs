@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use diesel::{
+    BelongingToDsl, BoolExpressionMethods, ExpressionMethods, GroupedBy, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+};
+use serde::Serialize;
+
+use crate::errors::{AppError, AppResult};
+use crate::grading::{
+    adjusted_score, component_average, grade_course, grade_distribution, GradeDistribution,
+};
+use crate::models::{Course, CourseComponent, CourseSubcomponent};
+use crate::routes::api::auth::callback::Session;
+use crate::schema::course::dsl::course;
+use crate::schema::course::{id, origin_id, share_grade_distribution};
+use crate::schema::study_block::dsl::study_block;
+use crate::schema::study_block::user_id;
+use crate::ServerState;
+
+/// The smallest number of grades that will be summarised, so that no individual's grades
+/// can be inferred from a distribution.
+const MINIMUM_COHORT_SIZE: usize = 5;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCourseDistribution {
+    pub cohort_size: usize,
+    pub course: Option<GradeDistribution>,
+    pub components: Vec<ComponentDistribution>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentDistribution {
+    pub component_id: String,
+    pub distribution: Option<GradeDistribution>,
+    pub subcomponents: Vec<SubcomponentDistribution>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubcomponentDistribution {
+    pub subcomponent_id: String,
+    pub distribution: Option<GradeDistribution>,
+}
+
+/// Returns anonymised grade distributions among every other user who has opted in to sharing
+/// their grades for a course cloned from the same origin. Components and subcomponents are
+/// matched to the requesting course by component name and position in sequence.
+///
+/// Each user is only counted once, and the requesting user is never counted, so that nobody
+/// can make up a cohort out of copies of their own course and infer the grades of the rest.
+pub async fn get_course_distribution(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<GetCourseDistribution>> {
+    let con = &mut state.get_db_con()?;
+
+    let selected_course = course
+        .find(&_course_id)
+        .select(Course::as_select())
+        .first(con)?;
+    if !selected_course.share_grade_distribution {
+        return AppError::bad_request(
+            "You must opt in to sharing your grades for this course to see how others are doing.",
+        )
+        .into();
+    }
+    let origin = selected_course
+        .origin_id
+        .clone()
+        .unwrap_or_else(|| selected_course.id.clone());
+
+    let mut cohort = course
+        .inner_join(study_block)
+        .filter(origin_id.eq(&origin).or(id.eq(&origin)))
+        .filter(share_grade_distribution.eq(true))
+        .filter(user_id.ne(&session.id))
+        .order((user_id.asc(), id.asc()))
+        .select((Course::as_select(), user_id))
+        .load::<(Course, String)>(con)?;
+    cohort.dedup_by(|(_, a), (_, b)| a == b);
+    let cohort = cohort.into_iter().map(|(c, _)| c).collect::<Vec<Course>>();
+
+    let mut loaded_courses = cohort.clone();
+    loaded_courses.push(selected_course.clone());
+    let cohort_components = CourseComponent::belonging_to(&loaded_courses)
+        .select(CourseComponent::as_select())
+        .load(con)?;
+    let cohort_components = CourseSubcomponent::belonging_to(&cohort_components)
+        .select(CourseSubcomponent::as_select())
+        .load(con)?
+        .grouped_by(&cohort_components)
+        .into_iter()
+        .zip(cohort_components)
+        .map(|(subcomponents, component)| (component, subcomponents))
+        .collect::<Vec<(CourseComponent, Vec<CourseSubcomponent>)>>();
+
+    let course_grades = cohort
+        .iter()
+        .filter_map(|c| {
+            grade_course(
+                cohort_components
+                    .iter()
+                    .filter(|(component, _)| component.course_id == c.id)
+                    .map(|(component, subcomponents)| (component, subcomponents.as_slice())),
+            )
+            .projected
+        })
+        .collect();
+
+    let mut own_components = cohort_components
+        .iter()
+        .filter(|(component, _)| component.course_id == selected_course.id)
+        .collect::<Vec<&(CourseComponent, Vec<CourseSubcomponent>)>>();
+    own_components.sort_by_key(|(component, _)| component.sequence_number);
+
+    let components = own_components
+        .into_iter()
+        .map(|(own_component, own_subcomponents)| {
+            let matching = cohort_components
+                .iter()
+                .filter(|(component, _)| {
+                    component.course_id != selected_course.id
+                        && component.name == own_component.name
+                })
+                .collect::<Vec<&(CourseComponent, Vec<CourseSubcomponent>)>>();
+
+            let mut own_subcomponents = own_subcomponents.iter().collect::<Vec<_>>();
+            own_subcomponents.sort_by_key(|s| s.number_in_sequence);
+
+            ComponentDistribution {
+                component_id: own_component.id.clone(),
+                distribution: grade_distribution(
+                    matching
+                        .iter()
+                        .filter_map(|(component, subcomponents)| {
+                            component_average(component, subcomponents, None)
+                        })
+                        .collect(),
+                    MINIMUM_COHORT_SIZE,
+                ),
+                subcomponents: own_subcomponents
+                    .into_iter()
+                    .map(|own_subcomponent| SubcomponentDistribution {
+                        subcomponent_id: own_subcomponent.id.clone(),
+                        distribution: grade_distribution(
+                            matching
+                                .iter()
+                                .flat_map(|(component, subcomponents)| {
+                                    subcomponents.iter().map(move |s| (component, s))
+                                })
+                                .filter(|(_, s)| {
+                                    s.is_completed
+                                        && s.number_in_sequence
+                                            == own_subcomponent.number_in_sequence
+                                })
+                                .map(|(component, s)| adjusted_score(component, s))
+                                .collect(),
+                            MINIMUM_COHORT_SIZE,
+                        ),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(Json(GetCourseDistribution {
+        cohort_size: cohort.len(),
+        course: grade_distribution(course_grades, MINIMUM_COHORT_SIZE),
+        components,
+    }))
+}
@@ -1,2 +1,9 @@
 pub(crate) mod component;
+pub(crate) mod distribution;
 pub(crate) mod order;
+pub(crate) mod publish;
+pub(crate) mod scale;
+pub(crate) mod scenario;
+pub(crate) mod share;
+pub(crate) mod target;
+pub(crate) mod transfer;
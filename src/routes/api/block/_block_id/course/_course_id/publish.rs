@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use cuid2::cuid;
+use diesel::{insert_into, QueryDsl, RunQueryDsl, SelectableHelper};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{Course, CourseTemplate};
+use crate::routes::api::auth::callback::Session;
+use crate::routes::api::block::_block_id::import::load_course_structure;
+use crate::routes::api::template::TemplateComponent;
+use crate::schema::course::dsl::course;
+use crate::schema::course_template::dsl::course_template;
+use crate::ServerState;
+
+/// The longest name or institution a template can have.
+const MAXIMUM_NAME_LENGTH: usize = 191;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishCourse {
+    pub name: String,
+    pub institution: String,
+}
+
+/// Publishes the structure of a course to the public template catalogue.
+/// Grades and completion status are never published.
+pub async fn publish_course(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+    Json(publish_data): Json<PublishCourse>,
+) -> AppResult<Json<CourseTemplate>> {
+    if publish_data.name.trim().is_empty() || publish_data.institution.trim().is_empty() {
+        return AppError::bad_request("Templates must have a name and an institution.").into();
+    }
+    if publish_data.name.trim().chars().count() > MAXIMUM_NAME_LENGTH
+        || publish_data.institution.trim().chars().count() > MAXIMUM_NAME_LENGTH
+    {
+        return AppError::bad_request(
+            "Template names and institutions must be 191 characters or fewer.",
+        )
+        .into();
+    }
+
+    let con = &mut state.get_db_con()?;
+
+    let original_course = course
+        .find(&_course_id)
+        .select(Course::as_select())
+        .first(con)?;
+    let structure = load_course_structure(con, &original_course)?
+        .into_iter()
+        .map(|(component, subcomponents)| {
+            TemplateComponent::from_structure(component, subcomponents)
+        })
+        .collect::<Vec<TemplateComponent>>();
+
+    let template = CourseTemplate {
+        id: cuid(),
+        user_id: session.id.clone(),
+        name: publish_data.name.trim().to_string(),
+        institution: publish_data.institution.trim().to_string(),
+        long_name: original_course.long_name,
+        course_code_name: original_course.course_code_name,
+        course_code_number: original_course.course_code_number,
+        color: original_course.color,
+        structure: serde_json::to_value(structure)
+            .or_else(|_| AppError::unspecified_ise().into())?,
+        created_at: OffsetDateTime::now_utc(),
+    };
+    insert_into(course_template)
+        .values(&template)
+        .execute(con)?;
+
+    Ok(Json(template))
+}
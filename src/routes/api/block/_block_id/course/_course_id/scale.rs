@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use diesel::{update, ExpressionMethods, QueryDsl, RunQueryDsl};
+use serde::Deserialize;
+
+use crate::errors::AppResult;
+use crate::routes::api::auth::callback::Session;
+use crate::routes::api::block::_block_id::course::course_id::get_course;
+use crate::routes::api::scale::find_usable_scale;
+use crate::routes::api::users::me::GetUserCourse;
+use crate::schema::course::dsl::course;
+use crate::schema::course::{grading_scale_id, id};
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCourseGradingScale {
+    /// The grading scale to use for this course, or `None` to use the user's own grade map.
+    pub scale_id: Option<String>,
+}
+
+pub async fn set_course_grading_scale(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(scale_data): Json<SetCourseGradingScale>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+
+    if let Some(_scale_id) = &scale_data.scale_id {
+        find_usable_scale(con, _scale_id, &session.id)?;
+    }
+    update(course.filter(id.eq(&_course_id)))
+        .set(grading_scale_id.eq(scale_data.scale_id))
+        .execute(con)?;
+
+    get_course(Path((_block_id, _course_id)), Extension(state)).await
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use bigdecimal::BigDecimal;
+use diesel::{
+    delete, update, BelongingToDsl, Connection, ExpressionMethods, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+};
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{Scenario, ScenarioSubcomponent};
+use crate::routes::api::block::_block_id::course::_course_id::scenario::validate_scenario_scores;
+use crate::routes::api::block::_block_id::course::course_id::get_course;
+use crate::routes::api::users::me::GetUserCourse;
+use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::schema::course_subcomponent::{
+    grade_value_percentage, is_completed, points_earned, points_possible,
+};
+use crate::schema::scenario::dsl::scenario;
+use crate::ServerState;
+
+/// Writes every hypothetical score in a scenario to the real subcomponents it refers to,
+/// marking them as completed, and then removes the scenario.
+pub async fn promote_scenario(
+    Path((_block_id, _course_id, _scenario_id)): Path<(String, String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+
+    con.transaction(|txn| {
+        let selected_scenario = scenario
+            .find(&_scenario_id)
+            .select(Scenario::as_select())
+            .get_result(txn)?;
+        let scores = ScenarioSubcomponent::belonging_to(&selected_scenario)
+            .select(ScenarioSubcomponent::as_select())
+            .load(txn)?;
+        // The maximum scores of components may have been lowered since the scenario was saved.
+        validate_scenario_scores(
+            txn,
+            &_course_id,
+            &scores
+                .iter()
+                .map(|s| (s.subcomponent_id.clone(), s.grade_value_percentage.clone()))
+                .collect(),
+        )?;
+
+        for score in scores {
+            update(
+                course_subcomponent
+                    .filter(crate::schema::course_subcomponent::id.eq(&score.subcomponent_id)),
+            )
+            .set((
+                grade_value_percentage.eq(score.grade_value_percentage),
+                is_completed.eq(true),
+                points_earned.eq(None::<BigDecimal>),
+                points_possible.eq(None::<BigDecimal>),
+            ))
+            .execute(txn)?;
+        }
+
+        delete(&selected_scenario).execute(txn)?;
+        Ok::<(), AppError>(())
+    })?;
+
+    get_course(Path((_block_id, _course_id)), Extension(state)).await
+}
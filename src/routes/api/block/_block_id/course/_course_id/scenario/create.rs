@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use bigdecimal::BigDecimal;
+use cuid2::cuid;
+use diesel::{insert_into, Connection, RunQueryDsl};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::Scenario;
+use crate::routes::api::block::_block_id::course::_course_id::scenario::replace_scenario_scores;
+use crate::routes::api::block::_block_id::course::course_id::get_course;
+use crate::routes::api::users::me::GetUserCourse;
+use crate::schema::scenario::dsl::scenario;
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateScenario {
+    pub name: String,
+    pub scores: HashMap<String, BigDecimal>,
+}
+
+pub async fn create_scenario(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(scenario_data): Json<CreateScenario>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+
+    con.transaction(|txn| {
+        let new_scenario = Scenario {
+            id: cuid(),
+            course_id: _course_id.clone(),
+            name: scenario_data.name,
+            created_at: OffsetDateTime::now_utc(),
+        };
+        insert_into(scenario).values(&new_scenario).execute(txn)?;
+        replace_scenario_scores(txn, &_course_id, &new_scenario.id, scenario_data.scores)?;
+        Ok::<(), AppError>(())
+    })?;
+
+    get_course(Path((_block_id, _course_id)), Extension(state)).await
+}
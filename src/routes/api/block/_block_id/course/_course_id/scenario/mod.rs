@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use bigdecimal::{BigDecimal, Zero};
+use diesel::{insert_into, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+use crate::errors::{AppError, AppResult};
+use crate::models::ScenarioSubcomponent;
+use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::schema::scenario_subcomponent::dsl::scenario_subcomponent;
+
+pub(crate) mod _scenario_id;
+pub(crate) mod create;
+pub(crate) mod scenario_id;
+
+/// Replaces all hypothetical scores in a scenario, ensuring that every score is a valid
+/// score for a subcomponent that belongs to the scenario's course.
+pub(crate) fn replace_scenario_scores(
+    con: &mut PgConnection,
+    _course_id: &str,
+    _scenario_id: &str,
+    scores: HashMap<String, BigDecimal>,
+) -> AppResult<()> {
+    if scores.len() > 100 {
+        return AppError::bad_request("Cannot set more than 100 scores in a scenario.").into();
+    }
+    validate_scenario_scores(con, _course_id, &scores)?;
+
+    diesel::delete(
+        scenario_subcomponent
+            .filter(crate::schema::scenario_subcomponent::scenario_id.eq(_scenario_id)),
+    )
+    .execute(con)?;
+    insert_into(scenario_subcomponent)
+        .values(
+            scores
+                .into_iter()
+                .map(
+                    |(subcomponent_id, grade_value_percentage)| ScenarioSubcomponent {
+                        scenario_id: _scenario_id.to_string(),
+                        subcomponent_id,
+                        grade_value_percentage,
+                    },
+                )
+                .collect::<Vec<ScenarioSubcomponent>>(),
+        )
+        .execute(con)?;
+
+    Ok(())
+}
+
+/// Ensures that every score is within the current maximum score of its subcomponent's component,
+/// and that every subcomponent belongs to the course.
+pub(crate) fn validate_scenario_scores(
+    con: &mut PgConnection,
+    _course_id: &str,
+    scores: &HashMap<String, BigDecimal>,
+) -> AppResult<()> {
+    if scores.values().any(|score| score.lt(&BigDecimal::zero())) {
+        return AppError::bad_request("Scenario scores can't be lower than zero.").into();
+    }
+
+    let maximum_scores: HashMap<String, BigDecimal> = course_subcomponent
+        .inner_join(course_component)
+        .filter(crate::schema::course_component::course_id.eq(_course_id))
+        .select((
+            crate::schema::course_subcomponent::id,
+            crate::schema::course_component::maximum_score,
+        ))
+        .load::<(String, BigDecimal)>(con)?
+        .into_iter()
+        .collect();
+    for (subcomponent_id, score) in scores {
+        match maximum_scores.get(subcomponent_id) {
+            None => return Err(AppError::resource_access_denied()),
+            Some(maximum_score) if score > maximum_score => {
+                return AppError::bad_request(
+                    "Scenario scores can't be higher than their component's maximum score.",
+                )
+                .into()
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use bigdecimal::BigDecimal;
+use diesel::{delete, update, Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use serde::Deserialize;
+
+use crate::errors::{AppError, AppResult};
+use crate::routes::api::block::_block_id::course::_course_id::scenario::replace_scenario_scores;
+use crate::routes::api::block::_block_id::course::course_id::get_course;
+use crate::routes::api::users::me::GetUserCourse;
+use crate::schema::scenario::dsl::scenario;
+use crate::schema::scenario::{id, name};
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateScenario {
+    pub name: Option<String>,
+    pub scores: Option<HashMap<String, BigDecimal>>,
+}
+
+pub async fn update_scenario(
+    Path((_block_id, _course_id, _scenario_id)): Path<(String, String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(scenario_data): Json<UpdateScenario>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+
+    con.transaction(|txn| {
+        if let Some(new_name) = scenario_data.name {
+            update(scenario.filter(id.eq(&_scenario_id)))
+                .set(name.eq(new_name))
+                .execute(txn)?;
+        }
+        if let Some(scores) = scenario_data.scores {
+            replace_scenario_scores(txn, &_course_id, &_scenario_id, scores)?;
+        }
+        Ok::<(), AppError>(())
+    })?;
+
+    get_course(Path((_block_id, _course_id)), Extension(state)).await
+}
+
+pub async fn delete_scenario(
+    Path((_block_id, _course_id, _scenario_id)): Path<(String, String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+
+    let result = delete(scenario.filter(id.eq(&_scenario_id))).execute(con)?;
+    if result != 1 {
+        return Err(AppError::resource_not_found());
+    }
+
+    get_course(Path((_block_id, _course_id)), Extension(state)).await
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use cuid2::CuidConstructor;
+use diesel::result::{DatabaseErrorKind, Error};
+use diesel::{insert_into, RunQueryDsl};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::CourseShare;
+use crate::schema::course_share::dsl::course_share;
+use crate::ServerState;
+
+const SHARE_CODE_GENERATOR: CuidConstructor = CuidConstructor::new().with_length(8);
+
+/// Share codes are short, so a new code is generated if one collides with an existing code.
+const MAXIMUM_ATTEMPTS: usize = 5;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCourseShare {
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+pub async fn create_course_share(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(share_data): Json<CreateCourseShare>,
+) -> AppResult<Json<CourseShare>> {
+    let now = OffsetDateTime::now_utc();
+    if share_data.expires_at.is_some_and(|expiry| expiry <= now) {
+        return AppError::bad_request("Share codes must expire in the future.").into();
+    }
+
+    let con = &mut state.get_db_con()?;
+    let mut attempts = 0;
+    loop {
+        let share = CourseShare {
+            code: SHARE_CODE_GENERATOR.create_id().to_uppercase(),
+            course_id: _course_id.clone(),
+            created_at: now,
+            expires_at: share_data.expires_at,
+        };
+        match insert_into(course_share).values(&share).execute(con) {
+            Ok(_) => return Ok(Json(share)),
+            Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _))
+                if attempts + 1 < MAXIMUM_ATTEMPTS =>
+            {
+                attempts += 1;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
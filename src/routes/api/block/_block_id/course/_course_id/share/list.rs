@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+
+use crate::errors::AppResult;
+use crate::models::CourseShare;
+use crate::schema::course_share::dsl::course_share;
+use crate::schema::course_share::{course_id, created_at};
+use crate::ServerState;
+
+pub async fn get_course_shares(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<Vec<CourseShare>>> {
+    let con = &mut state.get_db_con()?;
+
+    let shares = course_share
+        .filter(course_id.eq(&_course_id))
+        .order(created_at.desc())
+        .select(CourseShare::as_select())
+        .load(con)?;
+
+    Ok(Json(shares))
+}
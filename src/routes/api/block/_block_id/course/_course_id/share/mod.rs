@@ -0,0 +1,8 @@
+pub(crate) mod create;
+pub(crate) mod list;
+pub(crate) mod share_code;
+
+/// Share codes are stored in upper case, but are accepted in any case so that they're easy to type.
+pub(crate) fn normalise_share_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use diesel::{delete, ExpressionMethods, QueryDsl, RunQueryDsl};
+
+use crate::errors::{AppError, AppResult};
+use crate::routes::api::block::_block_id::course::_course_id::share::normalise_share_code;
+use crate::schema::course_share::dsl::course_share;
+use crate::schema::course_share::{code, course_id};
+use crate::ServerState;
+
+pub async fn revoke_course_share(
+    Path((_block_id, _course_id, _share_code)): Path<(String, String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Response> {
+    let con = &mut state.get_db_con()?;
+
+    let result = delete(
+        course_share
+            .filter(code.eq(normalise_share_code(&_share_code)))
+            .filter(course_id.eq(&_course_id)),
+    )
+    .execute(con)?;
+
+    (result == 1)
+        .then(|| StatusCode::OK.into_response())
+        .ok_or(AppError::resource_not_found())
+}
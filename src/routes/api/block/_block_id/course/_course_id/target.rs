@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::{Extension, Json};
+use bigdecimal::{BigDecimal, One, Zero};
+use diesel::{update, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppError, AppResult};
+use crate::grading::{required_score, RequiredScore};
+use crate::models::User;
+use crate::routes::api::auth::callback::Session;
+use crate::routes::api::block::_block_id::course::course_id::get_course;
+use crate::routes::api::users::me::GetUserCourse;
+use crate::schema::course::dsl::course;
+use crate::schema::course::{id, target, target_grade};
+use crate::schema::gk_user::dsl::gk_user;
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseTargetQuery {
+    pub grade: Option<String>,
+    pub percentage: Option<BigDecimal>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseTargetResponse {
+    pub target: BigDecimal,
+    #[serde(flatten)]
+    pub result: RequiredScore,
+}
+
+/// Resolves a target given as exactly one of a grade or a percentage. Grades are looked up in
+/// the course's grading scale, or the user's own grade map if the course doesn't have one.
+fn resolve_target(
+    selected_course: &GetUserCourse,
+    session: &Session,
+    state: &ServerState,
+    target_query: CourseTargetQuery,
+) -> AppResult<BigDecimal> {
+    let resolved_target = match (target_query.grade, target_query.percentage) {
+        (Some(grade), None) => {
+            // The course's grading scale takes precedence over the user's own grade map.
+            let course_grade_map = match selected_course.grade_map.clone() {
+                Some(course_grade_map) => course_grade_map,
+                None => {
+                    let con = &mut state.get_db_con()?;
+                    gk_user
+                        .find(&session.id)
+                        .select(User::as_select())
+                        .first(con)?
+                        .grade_map
+                }
+            };
+            course_grade_map
+                .threshold_for(&grade)
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::bad_request(format!(
+                        "'{}' is not a grade in this course's grade map.",
+                        grade
+                    ))
+                })?
+        }
+        (None, Some(percentage)) => percentage,
+        _ => {
+            return AppError::bad_request("Must specify exactly one of a grade or a percentage.")
+                .into()
+        }
+    };
+    if resolved_target < BigDecimal::zero() || resolved_target > BigDecimal::one() {
+        return AppError::bad_request("Target must be between 0% and 100%.").into();
+    }
+    Ok(resolved_target)
+}
+
+pub async fn get_course_target(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Query(query): Query<CourseTargetQuery>,
+) -> AppResult<Json<CourseTargetResponse>> {
+    let selected_course = get_course(Path((_block_id, _course_id)), Extension(state.clone()))
+        .await?
+        .0;
+
+    let resolved_target = resolve_target(&selected_course, &session, &state, query)?;
+
+    let components = selected_course
+        .components
+        .iter()
+        .map(|c| (&c.component, c.subcomponents.as_slice()))
+        .collect::<Vec<_>>();
+
+    Ok(Json(CourseTargetResponse {
+        result: required_score(&components, &resolved_target),
+        target: resolved_target,
+    }))
+}
+
+/// Saves a target on the course, so that course responses report whether it's at risk.
+pub async fn set_course_target(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(target_data): Json<CourseTargetQuery>,
+) -> AppResult<Json<GetUserCourse>> {
+    let selected_course = get_course(
+        Path((_block_id.clone(), _course_id.clone())),
+        Extension(state.clone()),
+    )
+    .await?
+    .0;
+
+    let new_target_grade = target_data.grade.clone();
+    let new_target = resolve_target(&selected_course, &session, &state, target_data)?;
+
+    let con = &mut state.get_db_con()?;
+    update(course.filter(id.eq(&_course_id)))
+        .set((target.eq(new_target), target_grade.eq(new_target_grade)))
+        .execute(con)?;
+
+    get_course(Path((_block_id, _course_id)), Extension(state)).await
+}
+
+pub async fn delete_course_target(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+    update(course.filter(id.eq(&_course_id)))
+        .set((
+            target.eq(None::<BigDecimal>),
+            target_grade.eq(None::<String>),
+        ))
+        .execute(con)?;
+
+    get_course(Path((_block_id, _course_id)), Extension(state)).await
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use diesel::{update, Connection, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use serde::Deserialize;
+
+use crate::errors::{AppError, AppResult};
+use crate::middleware::auth::user_owns_block;
+use crate::models::Course;
+use crate::routes::api::auth::callback::Session;
+use crate::routes::api::block::_block_id::course::course_id::get_course;
+use crate::routes::api::block::_block_id::import::clone_course;
+use crate::routes::api::users::me::GetUserCourse;
+use crate::schema::course::dsl::course;
+use crate::schema::course::{block_id, id};
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferCourse {
+    pub destination_block_id: String,
+    /// Only used when copying. Grades are reset in the copy if not set.
+    #[serde(default)]
+    pub preserve_grades: bool,
+}
+
+pub async fn move_course(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+    Json(transfer_data): Json<TransferCourse>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+
+    if !user_owns_block(con, &transfer_data.destination_block_id, &session.id) {
+        return Err(AppError::resource_access_denied());
+    }
+
+    update(course.filter(id.eq(&_course_id)))
+        .set(block_id.eq(&transfer_data.destination_block_id))
+        .execute(con)?;
+
+    get_course(
+        Path((transfer_data.destination_block_id, _course_id)),
+        Extension(state),
+    )
+    .await
+}
+
+pub async fn copy_course(
+    Path((_block_id, _course_id)): Path<(String, String)>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+    Json(transfer_data): Json<TransferCourse>,
+) -> AppResult<Json<GetUserCourse>> {
+    let con = &mut state.get_db_con()?;
+
+    if !user_owns_block(con, &transfer_data.destination_block_id, &session.id) {
+        return Err(AppError::resource_access_denied());
+    }
+
+    let original_course = course
+        .find(&_course_id)
+        .select(Course::as_select())
+        .first(con)?;
+    let new_course = con.transaction(|txn| {
+        clone_course(
+            txn,
+            &original_course,
+            transfer_data.destination_block_id.clone(),
+            transfer_data.preserve_grades,
+        )
+    })?;
+
+    get_course(
+        Path((transfer_data.destination_block_id, new_course.id)),
+        Extension(state),
+    )
+    .await
+}
@@ -2,17 +2,27 @@ use axum::extract::Path;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
+use bigdecimal::BigDecimal;
+use diesel::result::Error::QueryBuilderError;
 use diesel::{
-    delete, update, AsChangeset, BelongingToDsl, ExpressionMethods, GroupedBy, QueryDsl,
-    RunQueryDsl, SelectableHelper,
+    delete, update, AsChangeset, BelongingToDsl, Connection, ExpressionMethods, GroupedBy,
+    QueryDsl, RunQueryDsl, SelectableHelper,
 };
 use std::sync::Arc;
 
 use crate::errors::{AppError, AppResult};
-use crate::models::{Course, CourseComponent, CourseSubcomponent};
+use crate::grade_map::GradeMap;
+use crate::models::{
+    Course, CourseComponent, CourseSubcomponent, GradingScale, Scenario, ScenarioSubcomponent,
+};
+use crate::routes::api::block::_block_id::course::create::{validate_credits, validate_hurdle};
 use crate::routes::api::users::me::{GetUserComponent, GetUserCourse};
 use crate::schema::course::dsl::course;
-use crate::schema::course::id;
+use crate::schema::course::{hurdle, id};
+use crate::schema::gk_user::dsl::gk_user;
+use crate::schema::gk_user::grade_map;
+use crate::schema::grading_scale::dsl::grading_scale;
+use crate::schema::study_block::dsl::study_block;
 use crate::ServerState;
 use serde::Deserialize;
 
@@ -23,6 +33,11 @@ pub struct UpdateCourse {
     pub long_name: Option<String>,
     pub course_code_name: Option<String>,
     pub course_code_number: Option<String>,
+    pub share_grade_distribution: Option<bool>,
+    pub credits: Option<BigDecimal>,
+    /// A hurdle of zero removes the course's hurdle.
+    #[diesel(skip_update)]
+    pub hurdle: Option<BigDecimal>,
 }
 
 pub async fn update_course(
@@ -30,11 +45,32 @@ pub async fn update_course(
     Extension(state): Extension<Arc<ServerState>>,
     Json(_update_course): Json<UpdateCourse>,
 ) -> AppResult<Json<GetUserCourse>> {
+    if let Some(credits) = &_update_course.credits {
+        validate_credits(credits)?;
+    }
+
+    let new_hurdle = _update_course
+        .hurdle
+        .clone()
+        .map(|h| validate_hurdle(Some(h)))
+        .transpose()?;
+
     let con = &mut state.get_db_con()?;
 
-    update(course.filter(id.eq(&_course_id)))
-        .set(_update_course)
-        .execute(con)?;
+    con.transaction(|txn| {
+        if let Some(new_hurdle) = new_hurdle {
+            update(course.filter(id.eq(&_course_id)))
+                .set(hurdle.eq(new_hurdle))
+                .execute(txn)?;
+        }
+        match update(course.filter(id.eq(&_course_id)))
+            .set(_update_course)
+            .execute(txn)
+        {
+            Ok(_) | Err(QueryBuilderError(_)) => Ok(()),
+            Err(e) => Err(AppError::database_ise(e)),
+        }
+    })?;
 
     get_course(Path((_block_id, _course_id)), Extension(state)).await
 }
@@ -65,19 +101,45 @@ pub async fn get_course(
     let course_components: Vec<CourseComponent> = CourseComponent::belonging_to(&selected_course)
         .select(CourseComponent::as_select())
         .load(con)?;
+    let scenarios: Vec<Scenario> = Scenario::belonging_to(&selected_course)
+        .select(Scenario::as_select())
+        .load(con)?;
+    let scenario_scores = ScenarioSubcomponent::belonging_to(&scenarios)
+        .select(ScenarioSubcomponent::as_select())
+        .load(con)?
+        .grouped_by(&scenarios)
+        .into_iter()
+        .zip(scenarios)
+        .map(|(scores, scenario)| (scenario, scores))
+        .collect::<Vec<(Scenario, Vec<ScenarioSubcomponent>)>>();
+    let scale = selected_course
+        .grading_scale_id
+        .as_ref()
+        .map(|_scale_id| {
+            grading_scale
+                .find(_scale_id)
+                .select(GradingScale::as_select())
+                .first(con)
+        })
+        .transpose()?;
+    let user_grade_map: GradeMap = study_block
+        .inner_join(gk_user)
+        .filter(crate::schema::study_block::id.eq(&selected_course.block_id))
+        .select(grade_map)
+        .first(con)?;
 
-    Ok(Json(GetUserCourse {
-        course: selected_course,
-        components: CourseSubcomponent::belonging_to(&course_components)
+    Ok(Json(GetUserCourse::new(
+        selected_course,
+        CourseSubcomponent::belonging_to(&course_components)
             .select(CourseSubcomponent::as_select())
             .load(con)?
             .grouped_by(&course_components)
             .into_iter()
             .zip(course_components)
-            .map(|(sub, comp)| GetUserComponent {
-                component: comp,
-                subcomponents: sub,
-            })
+            .map(|(sub, comp)| GetUserComponent::new(comp, sub))
             .collect::<Vec<GetUserComponent>>(),
-    }))
+        scenario_scores,
+        scale,
+        &user_grade_map,
+    )))
 }
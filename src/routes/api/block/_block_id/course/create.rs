@@ -4,7 +4,7 @@ use std::sync::Arc;
 use axum::extract::Path;
 use axum::{Extension, Json};
 
-use bigdecimal::{BigDecimal, Zero};
+use bigdecimal::{BigDecimal, One, Zero};
 use cuid2::cuid;
 use diesel::result::Error;
 use diesel::{Connection, RunQueryDsl};
@@ -13,6 +13,7 @@ use crate::errors::AppError;
 use crate::models::{Course, CourseComponent, CourseSubcomponent};
 use serde::{Deserialize, Serialize};
 
+use crate::scaling::ScalingRule;
 use crate::schema::course::dsl::course;
 use crate::schema::course_component::dsl::course_component;
 use crate::schema::course_subcomponent::dsl::course_subcomponent;
@@ -32,6 +33,7 @@ pub struct CreateCourse {
     #[serde(rename = "codeNo")]
     pub course_code_number: String,
     pub color: String,
+    pub credits: Option<BigDecimal>,
 
     pub components: Vec<CreateCourseComponent>,
 }
@@ -43,6 +45,24 @@ pub struct CreateCourseComponent {
     pub weighting: BigDecimal,
     pub name: String,
     pub number_of_subcomponents: String,
+    /// The minimum grade needed on this component to pass the course. A hurdle of zero means
+    /// the component has no hurdle.
+    #[serde(default)]
+    pub hurdle: Option<BigDecimal>,
+    #[serde(default)]
+    pub is_bonus: bool,
+    /// The highest score a subcomponent can have. Defaults to 100%.
+    #[serde(default)]
+    pub maximum_score: Option<BigDecimal>,
+    /// The fraction of full marks deducted for each day a subcomponent is late. Zero means
+    /// late submissions aren't penalised.
+    #[serde(default)]
+    pub late_penalty_per_day: Option<BigDecimal>,
+    /// The most that can be deducted for being late. Zero means there's no limit.
+    #[serde(default)]
+    pub late_penalty_cap: Option<BigDecimal>,
+    #[serde(default)]
+    pub scaling: Option<ScalingRule>,
 }
 
 fn validate(course_data: &CreateCourse) -> Result<(), AppError> {
@@ -68,18 +88,112 @@ fn validate(course_data: &CreateCourse) -> Result<(), AppError> {
         ));
     }
 
-    if course_data
-        .components
-        .iter()
-        .map(|d| d.weighting.clone())
-        .reduce(|a, b| a.add(b))
-        .unwrap_or(BigDecimal::from(0))
-        .ne(&BigDecimal::from(1))
-    {
+    if let Some(credits) = &course_data.credits {
+        validate_credits(credits)?;
+    }
+    for component in &course_data.components {
+        match component.is_bonus {
+            true => validate_bonus_weighting(&component.weighting)?,
+            false => validate_component_weighting(&component.weighting)?,
+        }
+        validate_hurdle(component.hurdle.clone())?;
+        validate_late_penalty(component.late_penalty_per_day.clone())?;
+        validate_late_penalty(component.late_penalty_cap.clone())?;
+        if let Some(scaling) = &component.scaling {
+            scaling.validate().map_err(AppError::bad_request)?;
+        }
+        if let Some(maximum_score) = &component.maximum_score {
+            validate_maximum_score(maximum_score)?;
+        }
+    }
+
+    validate_weighting_total(
+        course_data
+            .components
+            .iter()
+            .filter(|d| !d.is_bonus)
+            .map(|d| &d.weighting),
+    )
+}
+
+/// Extra credit is limited to 500%, which keeps scores within the `numeric(5,4)` columns.
+pub(crate) fn validate_maximum_score(maximum_score: &BigDecimal) -> Result<(), AppError> {
+    if *maximum_score < BigDecimal::one() || *maximum_score > BigDecimal::new(5.into(), 0) {
+        return Err(AppError::bad_request(
+            "Maximum score must be between 100% and 500%.",
+        ));
+    }
+    Ok(())
+}
+
+/// Bonus components aren't counted towards the course's total weighting,
+/// so their weightings are bounded separately.
+pub(crate) fn validate_bonus_weighting(weighting: &BigDecimal) -> Result<(), AppError> {
+    if *weighting <= BigDecimal::zero() || *weighting > BigDecimal::one() {
+        return Err(AppError::bad_request(
+            "Bonus component weighting must be more than 0% and at most 100%.",
+        ));
+    }
+    Ok(())
+}
+
+/// Component weightings are stored as `numeric(5,4)`, and no component can be worth more than
+/// the whole course.
+pub(crate) fn validate_component_weighting(weighting: &BigDecimal) -> Result<(), AppError> {
+    if *weighting < BigDecimal::zero() || *weighting > BigDecimal::one() {
         return Err(AppError::bad_request(
-            "Course components must add up to 100%.",
+            "Component weighting must be between 0% and 100%.",
         ));
     }
+    Ok(())
+}
+
+/// Validates a hurdle, returning `None` if there's no hurdle. A hurdle of zero is treated as
+/// no hurdle at all, so that clients can remove a hurdle.
+pub(crate) fn validate_hurdle(hurdle: Option<BigDecimal>) -> Result<Option<BigDecimal>, AppError> {
+    match hurdle {
+        Some(h) if h < BigDecimal::zero() || h > BigDecimal::one() => Err(AppError::bad_request(
+            "Hurdles must be between 0% and 100%.",
+        )),
+        Some(h) if h.is_zero() => Ok(None),
+        h => Ok(h),
+    }
+}
+
+/// Validates a late penalty or late penalty cap, returning `None` if it's zero, so that
+/// clients can remove it.
+pub(crate) fn validate_late_penalty(
+    penalty: Option<BigDecimal>,
+) -> Result<Option<BigDecimal>, AppError> {
+    match penalty {
+        Some(p) if p < BigDecimal::zero() || p > BigDecimal::one() => Err(AppError::bad_request(
+            "Late penalties must be between 0% and 100%.",
+        )),
+        Some(p) if p.is_zero() => Ok(None),
+        p => Ok(p),
+    }
+}
+
+pub(crate) fn validate_credits(credits: &BigDecimal) -> Result<(), AppError> {
+    if *credits < BigDecimal::zero() || *credits >= BigDecimal::new(1.into(), -4) {
+        return Err(AppError::bad_request(
+            "Credits must be at least 0 and less than 10000.",
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_weighting_total<'a>(
+    weightings: impl IntoIterator<Item = &'a BigDecimal>,
+) -> Result<(), AppError> {
+    let total = weightings
+        .into_iter()
+        .cloned()
+        .reduce(|a, b| a.add(b))
+        .unwrap_or(BigDecimal::from(0));
+    if total.ne(&BigDecimal::from(1)) {
+        return Err(AppError::invalid_weighting(total));
+    }
 
     Ok(())
 }
@@ -101,6 +215,13 @@ pub async fn create_course(
             course_code_number: Some(course_data.course_code_number),
             block_id: _block_id,
             color: course_data.color,
+            origin_id: None,
+            share_grade_distribution: false,
+            grading_scale_id: None,
+            credits: course_data.credits.unwrap_or_else(BigDecimal::one),
+            hurdle: None,
+            target: None,
+            target_grade: None,
         };
 
         let mut new_components: Vec<CourseComponent> = vec![];
@@ -115,6 +236,18 @@ pub async fn create_course(
                 number_of_subcomponents_to_drop_lowest: component.drop_lowest,
                 name_of_subcomponent_singular: "".to_string(),
                 sequence_number: Some((i + 1) as i16),
+                // Hurdles have already been validated above.
+                hurdle: validate_hurdle(component.hurdle).ok().flatten(),
+                is_bonus: component.is_bonus,
+                maximum_score: component.maximum_score.unwrap_or_else(BigDecimal::one),
+                late_penalty_per_day: validate_late_penalty(component.late_penalty_per_day)
+                    .ok()
+                    .flatten(),
+                late_penalty_cap: validate_late_penalty(component.late_penalty_cap)
+                    .ok()
+                    .flatten(),
+                scaling: component.scaling,
+                is_scaling_enabled: true,
             };
             let n_subc = component.number_of_subcomponents.parse::<i32>().unwrap();
             for i in 1..(n_subc + 1) {
@@ -125,6 +258,12 @@ pub async fn create_course(
                     is_completed: false,
                     number_in_sequence: i,
                     override_name: None,
+                    points_earned: None,
+                    points_possible: None,
+                    weighting: None,
+                    due_at: None,
+                    submitted_at: None,
+                    extended_due_at: None,
                 };
                 new_subcomponents.push(new_subcomponent);
             }
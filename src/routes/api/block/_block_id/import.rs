@@ -2,24 +2,34 @@ use axum::extract::Path;
 use axum::{Extension, Json};
 use std::sync::Arc;
 
-use bigdecimal::{BigDecimal, Zero};
+use bigdecimal::{BigDecimal, One, Zero};
 use cuid2::cuid;
-use diesel::{insert_into, BelongingToDsl, GroupedBy, QueryDsl, RunQueryDsl, SelectableHelper};
+use diesel::{
+    insert_into, BelongingToDsl, BoolExpressionMethods, Connection, ExpressionMethods, GroupedBy,
+    PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+use time::OffsetDateTime;
 
-use crate::errors::AppError;
-use crate::models::{Course, CourseComponent, CourseSubcomponent};
+use crate::errors::{AppError, AppResult};
+use crate::models::{Course, CourseComponent, CourseSubcomponent, CourseTemplate};
 use crate::routes::api::auth::callback::Session;
+use crate::routes::api::block::_block_id::course::_course_id::share::normalise_share_code;
+use crate::routes::api::template::TemplateComponent;
 use serde::Deserialize;
 
 use crate::schema::course::dsl::course;
 use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_share::dsl::course_share;
+use crate::schema::course_share::{code, expires_at};
 use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::schema::course_template::dsl::course_template;
 use crate::ServerState;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportCourse {
-    pub share_code: String,
+    pub share_code: Option<String>,
+    pub template_id: Option<String>,
 }
 pub(crate) async fn import_course(
     Path(block_id): Path<String>,
@@ -28,18 +38,91 @@ pub(crate) async fn import_course(
     Json(course_request): Json<ImportCourse>,
 ) -> Result<Json<Course>, AppError> {
     let con = &mut state.get_db_con()?;
-    let original_course = course
-        .find(&course_request.share_code)
-        .select(Course::as_select())
-        .first(con)
-        .or_else(|_| AppError::resource_not_found().into())?;
 
-    let original_components = CourseComponent::belonging_to(&original_course)
+    let new_course = match (course_request.share_code, course_request.template_id) {
+        (Some(share_code), None) => {
+            let original_course = course_share
+                .inner_join(course)
+                .filter(code.eq(normalise_share_code(&share_code)))
+                .filter(
+                    expires_at
+                        .is_null()
+                        .or(expires_at.gt(OffsetDateTime::now_utc())),
+                )
+                .select(Course::as_select())
+                .first(con)
+                .or_else(|_| AppError::resource_not_found().into())?;
+            // The sharer's grading scale may not be visible to whoever imports the course,
+            // so the copy falls back to the importer's own grade map.
+            let original_course = Course {
+                grading_scale_id: None,
+                ..original_course
+            };
+
+            con.transaction(|txn| clone_course(txn, &original_course, block_id, false))?
+        }
+        (None, Some(template_id)) => {
+            let template = course_template
+                .find(&template_id)
+                .select(CourseTemplate::as_select())
+                .first(con)
+                .or_else(|_| AppError::resource_not_found().into())?;
+            let structure = serde_json::from_value::<Vec<TemplateComponent>>(template.structure)
+                .or_else(|_| AppError::unspecified_ise().into())?
+                .into_iter()
+                .map(TemplateComponent::into_structure)
+                .collect();
+            let template_course = Course {
+                id: template.id,
+                long_name: template.long_name,
+                course_code_name: template.course_code_name,
+                course_code_number: template.course_code_number,
+                block_id: block_id.clone(),
+                color: template.color,
+                origin_id: None,
+                share_grade_distribution: false,
+                grading_scale_id: None,
+                credits: BigDecimal::one(),
+                hurdle: None,
+                target: None,
+                target_grade: None,
+            };
+
+            con.transaction(|txn| {
+                insert_course_copy(txn, &template_course, structure, block_id, false)
+            })?
+        }
+        _ => {
+            return AppError::bad_request("Must specify exactly one of a share code or a template.")
+                .into()
+        }
+    };
+
+    Ok(Json(new_course))
+}
+
+/// Copies the structure of a course, including all of its components and subcomponents,
+/// into a study block. Grades and completion status are only copied if `preserve_grades` is set.
+pub(crate) fn clone_course(
+    con: &mut PgConnection,
+    original_course: &Course,
+    block_id: String,
+    preserve_grades: bool,
+) -> AppResult<Course> {
+    let structure = load_course_structure(con, original_course)?;
+    insert_course_copy(con, original_course, structure, block_id, preserve_grades)
+}
+
+pub(crate) fn load_course_structure(
+    con: &mut PgConnection,
+    original_course: &Course,
+) -> AppResult<Vec<(CourseComponent, Vec<CourseSubcomponent>)>> {
+    let original_components = CourseComponent::belonging_to(original_course)
         .select(CourseComponent::as_select())
         .load(con)
         .or_else(|_| AppError::unspecified_ise().into())?;
 
-    let original_subcomponents = CourseSubcomponent::belonging_to(&original_components)
+    Ok(CourseSubcomponent::belonging_to(&original_components)
         .select(CourseSubcomponent::as_select())
         .load(con)
         .or_else(|_| AppError::unspecified_ise().into())?
@@ -47,8 +130,18 @@ pub(crate) async fn import_course(
         .into_iter()
         .zip(original_components)
         .map(|(subcomponents, component)| (component, subcomponents))
-        .collect::<Vec<(CourseComponent, Vec<CourseSubcomponent>)>>();
+        .collect::<Vec<(CourseComponent, Vec<CourseSubcomponent>)>>())
+}
 
+/// Inserts a copy of a course and the given structure into a study block, with new IDs.
+/// The copy keeps track of the course it was originally cloned from.
+pub(crate) fn insert_course_copy(
+    con: &mut PgConnection,
+    original_course: &Course,
+    original_subcomponents: Vec<(CourseComponent, Vec<CourseSubcomponent>)>,
+    block_id: String,
+    preserve_grades: bool,
+) -> AppResult<Course> {
     let new_course_id = cuid();
     let new_course = Course {
         id: new_course_id.clone(),
@@ -57,6 +150,22 @@ pub(crate) async fn import_course(
         course_code_number: original_course.course_code_number.clone(),
         block_id,
         color: original_course.color.clone(),
+        origin_id: Some(
+            original_course
+                .origin_id
+                .clone()
+                .unwrap_or_else(|| original_course.id.clone()),
+        ),
+        share_grade_distribution: false,
+        grading_scale_id: original_course.grading_scale_id.clone(),
+        credits: original_course.credits.clone(),
+        hurdle: original_course.hurdle.clone(),
+        // Targets are set by each user against their own grades.
+        target: original_course.target.clone().filter(|_| preserve_grades),
+        target_grade: original_course
+            .target_grade
+            .clone()
+            .filter(|_| preserve_grades),
     };
 
     let mut components: Vec<CourseComponent> = vec![];
@@ -71,16 +180,37 @@ pub(crate) async fn import_course(
             course_id: new_course_id.clone(),
             subject_weighting: c.subject_weighting,
             sequence_number: c.sequence_number,
+            hurdle: c.hurdle,
+            is_bonus: c.is_bonus,
+            maximum_score: c.maximum_score,
+            late_penalty_per_day: c.late_penalty_per_day,
+            late_penalty_cap: c.late_penalty_cap,
+            // Scaling is decided after the fact for a particular offering of a course,
+            // so is only relevant along with the grades.
+            scaling: c.scaling.filter(|_| preserve_grades),
+            is_scaling_enabled: c.is_scaling_enabled,
         };
         components.push(component);
         for subcomponent in split_subcomponent {
-            subcomponents.push(CourseSubcomponent {
+            let copied_subcomponent = CourseSubcomponent {
                 id: cuid(),
                 component_id: component_id.clone(),
-                grade_value_percentage: BigDecimal::zero(),
-                is_completed: false,
-                number_in_sequence: subcomponent.number_in_sequence,
-                override_name: subcomponent.override_name,
+                ..subcomponent
+            };
+            // Due dates, submission dates and extensions are only relevant to the same offering
+            // of a course, so are kept along with the grades.
+            subcomponents.push(match preserve_grades {
+                true => copied_subcomponent,
+                false => CourseSubcomponent {
+                    grade_value_percentage: BigDecimal::zero(),
+                    is_completed: false,
+                    points_earned: None,
+                    points_possible: None,
+                    due_at: None,
+                    submitted_at: None,
+                    extended_due_at: None,
+                    ..copied_subcomponent
+                },
             })
         }
     }
@@ -93,5 +223,5 @@ pub(crate) async fn import_course(
         .values(subcomponents)
         .execute(con)?;
 
-    Ok(Json(new_course))
+    Ok(new_course)
 }
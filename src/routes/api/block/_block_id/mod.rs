@@ -1,2 +1,3 @@
 pub(crate) mod course;
 pub(crate) mod import;
+pub(crate) mod rollover;
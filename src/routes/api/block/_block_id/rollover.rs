@@ -0,0 +1,78 @@
+use axum::extract::Path;
+use axum::{Extension, Json};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use cuid2::cuid;
+use diesel::{insert_into, Connection, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{Course, StudyBlock};
+use crate::routes::api::auth::callback::Session;
+use crate::routes::api::block::_block_id::import::clone_course;
+use crate::routes::api::block::block_id::validate_block_dates;
+use crate::schema::course::dsl::course;
+use crate::schema::course::{block_id, id};
+use crate::schema::study_block::dsl::study_block;
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloverBlock {
+    pub name: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub start_date: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub end_date: OffsetDateTime,
+    /// Courses to copy into the new block. Every course in the block is copied if not provided.
+    /// Duplicate IDs are ignored.
+    pub course_ids: Option<HashSet<String>>,
+}
+
+/// Creates a new study block with the same course structure as an existing block,
+/// with all grades reset.
+pub async fn rollover_block(
+    Path(_block_id): Path<String>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+    Json(rollover_data): Json<RolloverBlock>,
+) -> AppResult<Json<StudyBlock>> {
+    validate_block_dates(&rollover_data.start_date, &rollover_data.end_date)?;
+
+    let con = &mut state.get_db_con()?;
+
+    let new_block = con.transaction(|txn| {
+        let mut courses_query = course
+            .filter(block_id.eq(&_block_id))
+            .select(Course::as_select())
+            .into_boxed();
+        if let Some(course_ids) = &rollover_data.course_ids {
+            courses_query = courses_query.filter(id.eq_any(course_ids));
+        }
+        let courses = courses_query.load(txn)?;
+        if let Some(course_ids) = &rollover_data.course_ids {
+            if courses.len() != course_ids.len() {
+                return Err(AppError::resource_access_denied());
+            }
+        }
+
+        let block = StudyBlock {
+            end_date: rollover_data.end_date,
+            start_date: rollover_data.start_date,
+            id: cuid(),
+            name: rollover_data.name,
+            user_id: session.id.clone(),
+        };
+        insert_into(study_block).values(&block).execute(txn)?;
+
+        for original_course in &courses {
+            clone_course(txn, original_course, block.id.clone(), false)?;
+        }
+
+        Ok(block)
+    })?;
+
+    Ok(Json(new_block))
+}
@@ -1,13 +1,68 @@
-use crate::errors::AppError;
+use crate::errors::{AppError, AppResult};
+use crate::models::StudyBlock;
 use crate::routes::api::auth::callback::Session;
 use crate::schema::study_block::dsl::study_block;
 use crate::schema::study_block::{id, user_id};
 use crate::ServerState;
 use axum::extract::Path;
 use axum::http::StatusCode;
-use axum::Extension;
-use diesel::{BoolExpressionMethods, ExpressionMethods, RunQueryDsl};
+use axum::{Extension, Json};
+use diesel::result::Error::QueryBuilderError;
+use diesel::{
+    update, AsChangeset, BoolExpressionMethods, Connection, ExpressionMethods, QueryDsl,
+    RunQueryDsl, SelectableHelper,
+};
+use serde::Deserialize;
 use std::sync::Arc;
+use time::OffsetDateTime;
+
+#[derive(Deserialize, AsChangeset)]
+#[diesel(table_name=crate::schema::study_block)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBlock {
+    pub name: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub start_date: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub end_date: Option<OffsetDateTime>,
+}
+
+pub(crate) fn validate_block_dates(
+    start_date: &OffsetDateTime,
+    end_date: &OffsetDateTime,
+) -> AppResult<()> {
+    if start_date >= end_date {
+        return AppError::bad_request("Study block must start before it ends.").into();
+    }
+    Ok(())
+}
+
+pub async fn update_block(
+    Path(_id): Path<String>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+    Json(_update_block): Json<UpdateBlock>,
+) -> AppResult<Json<StudyBlock>> {
+    let con = &mut state.get_db_con()?;
+
+    con.transaction(|txn| {
+        match update(study_block.filter(id.eq(&_id).and(user_id.eq(&session.id))))
+            .set(&_update_block)
+            .execute(txn)
+        {
+            Ok(_) | Err(QueryBuilderError(_)) => {}
+            Err(e) => return Err(AppError::database_ise(e)),
+        }
+
+        let block = study_block
+            .filter(id.eq(&_id).and(user_id.eq(&session.id)))
+            .select(StudyBlock::as_select())
+            .get_result(txn)?;
+        validate_block_dates(&block.start_date, &block.end_date)?;
+
+        Ok(Json(block))
+    })
+}
 
 pub async fn delete_block(
     Path(_id): Path<String>,
@@ -22,12 +77,14 @@ pub async fn delete_block(
         .map_err(|_e| AppError {
             status_code: StatusCode::BAD_REQUEST,
             description: "Failed to delete study block.".to_string(),
+            details: None,
         })?;
 
     if rows == 0 {
         return Err(AppError {
             status_code: StatusCode::BAD_REQUEST,
             description: "No study block by that ID exists.".to_string(),
+            details: None,
         });
     }
 
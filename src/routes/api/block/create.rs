@@ -5,6 +5,7 @@ use std::sync::Arc;
 use crate::errors::AppError;
 use crate::models::StudyBlock;
 use crate::routes::api::auth::callback::Session;
+use crate::routes::api::block::block_id::validate_block_dates;
 use crate::schema::study_block::dsl::study_block;
 use crate::ServerState;
 use diesel::{insert_into, RunQueryDsl};
@@ -16,6 +17,8 @@ pub async fn create_block(
     Extension(state): Extension<Arc<ServerState>>,
     Json(payload): Json<CreateBlock>,
 ) -> Result<Json<StudyBlock>, AppError> {
+    validate_block_dates(&payload.start_date, &payload.end_date)?;
+
     let con = &mut state.get_db_con()?;
     let block = StudyBlock {
         end_date: payload.end_date,
@@ -31,6 +34,7 @@ pub async fn create_block(
         .map_err(|e| AppError {
             status_code: StatusCode::INTERNAL_SERVER_ERROR,
             description: format!("Could not create study block: {}", e),
+            details: None,
         })?;
 
     Ok(Json(block))
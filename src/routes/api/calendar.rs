@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use bigdecimal::{BigDecimal, RoundingMode};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::errors::AppResult;
+use crate::models::{CalendarFeed, Course, CourseComponent, CourseSubcomponent};
+use crate::schema::course::dsl::course;
+use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::schema::course_subcomponent::{component_id, due_at};
+use crate::schema::study_block::dsl::study_block;
+use crate::ServerState;
+
+/// iCalendar lines must be folded so that no line is longer than 75 octets.
+const MAXIMUM_LINE_LENGTH: usize = 75;
+
+fn format_date_time(date_time: OffsetDateTime) -> String {
+    let utc = date_time.to_offset(UtcOffset::UTC);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        utc.year(),
+        utc.month() as u8,
+        utc.day(),
+        utc.hour(),
+        utc.minute(),
+        utc.second()
+    )
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\r', '\n'], "\\n")
+}
+
+/// Writes a content line, folding it onto continuation lines (which start with a space)
+/// without splitting any UTF-8 characters.
+fn write_line(calendar: &mut String, line: &str) {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAXIMUM_LINE_LENGTH {
+            calendar.push_str("\r\n ");
+            length = 1;
+        }
+        calendar.push(c);
+        length += c.len_utf8();
+    }
+    calendar.push_str("\r\n");
+}
+
+fn course_label(parent_course: &Course) -> String {
+    let code = format!(
+        "{}{}",
+        parent_course
+            .course_code_name
+            .as_deref()
+            .unwrap_or_default(),
+        parent_course
+            .course_code_number
+            .as_deref()
+            .unwrap_or_default()
+    );
+    match (code.trim().is_empty(), &parent_course.long_name) {
+        (false, _) => code.trim().to_string(),
+        (true, Some(long_name)) => long_name.clone(),
+        (true, None) => "Course".to_string(),
+    }
+}
+
+fn subcomponent_label(component: &CourseComponent, subcomponent: &CourseSubcomponent) -> String {
+    match &subcomponent.override_name {
+        Some(name) if !name.trim().is_empty() => name.clone(),
+        _ => {
+            let singular = match component.name_of_subcomponent_singular.trim() {
+                "" => component.name.as_str(),
+                singular => singular,
+            };
+            format!("{} {}", singular, subcomponent.number_in_sequence)
+        }
+    }
+}
+
+/// The number of subcomponents in a component, and whether every one of them has a weighting.
+#[derive(Default)]
+struct Siblings {
+    count: i64,
+    all_weighted: bool,
+}
+
+/// The share of the whole course a subcomponent is worth, as a percentage.
+fn course_weighting(
+    component: &CourseComponent,
+    subcomponent: &CourseSubcomponent,
+    siblings: &Siblings,
+) -> BigDecimal {
+    let share = match siblings.all_weighted {
+        true => subcomponent.weighting.clone().unwrap_or_default(),
+        false => BigDecimal::from(1) / BigDecimal::from(siblings.count.max(1)),
+    };
+    (&component.subject_weighting * share * BigDecimal::from(100))
+        .with_scale_round(2, RoundingMode::HalfUp)
+        .normalized()
+}
+
+/// Renders every subcomponent with a due date, across all of the feed owner's study blocks,
+/// as an iCalendar feed that calendar apps can subscribe to. Extended due dates replace the
+/// original due date.
+pub async fn get_calendar(
+    Path(_feed_token): Path<String>,
+    Extension(feed): Extension<Arc<CalendarFeed>>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Response> {
+    let con = &mut state.get_db_con()?;
+
+    let subcomponents = course_subcomponent
+        .inner_join(course_component.inner_join(course.inner_join(study_block)))
+        .filter(crate::schema::study_block::user_id.eq(&feed.user_id))
+        .filter(due_at.is_not_null())
+        .select((
+            CourseSubcomponent::as_select(),
+            CourseComponent::as_select(),
+            Course::as_select(),
+        ))
+        .load::<(CourseSubcomponent, CourseComponent, Course)>(con)?;
+
+    let mut siblings: HashMap<String, Siblings> = HashMap::new();
+    for (sibling_component_id, sibling_weighting) in course_subcomponent
+        .filter(component_id.eq_any(subcomponents.iter().map(|(s, _, _)| &s.component_id)))
+        .select((component_id, crate::schema::course_subcomponent::weighting))
+        .load::<(String, Option<BigDecimal>)>(con)?
+    {
+        let entry = siblings.entry(sibling_component_id).or_insert(Siblings {
+            count: 0,
+            all_weighted: true,
+        });
+        entry.count += 1;
+        entry.all_weighted &= sibling_weighting.is_some();
+    }
+
+    let now = format_date_time(OffsetDateTime::now_utc());
+    let mut calendar = String::new();
+    write_line(&mut calendar, "BEGIN:VCALENDAR");
+    write_line(&mut calendar, "VERSION:2.0");
+    write_line(&mut calendar, "PRODID:-//Gradekeeper//Assessments//EN");
+    write_line(&mut calendar, "CALSCALE:GREGORIAN");
+    write_line(&mut calendar, "METHOD:PUBLISH");
+    write_line(&mut calendar, "X-WR-CALNAME:Gradekeeper");
+    for (subcomponent, component, parent_course) in &subcomponents {
+        let Some(subcomponent_due_at) = subcomponent.extended_due_at.or(subcomponent.due_at) else {
+            continue;
+        };
+        let subcomponent_due_at = format_date_time(subcomponent_due_at);
+        let weighting = course_weighting(
+            component,
+            subcomponent,
+            siblings
+                .get(&subcomponent.component_id)
+                .unwrap_or(&Siblings::default()),
+        );
+        let description = format!(
+            "{}\nComponent: {}\nWeighting: {}% of the course{}",
+            parent_course.long_name.as_deref().unwrap_or_default(),
+            component.name,
+            weighting,
+            if component.is_bonus { " (bonus)" } else { "" },
+        );
+
+        write_line(&mut calendar, "BEGIN:VEVENT");
+        write_line(
+            &mut calendar,
+            &format!("UID:{}@gradekeeper", subcomponent.id),
+        );
+        write_line(&mut calendar, &format!("DTSTAMP:{}", now));
+        write_line(&mut calendar, &format!("DTSTART:{}", subcomponent_due_at));
+        write_line(&mut calendar, &format!("DTEND:{}", subcomponent_due_at));
+        write_line(
+            &mut calendar,
+            &format!(
+                "SUMMARY:{}",
+                escape_text(&format!(
+                    "{}: {}",
+                    course_label(parent_course),
+                    subcomponent_label(component, subcomponent)
+                ))
+            ),
+        );
+        write_line(
+            &mut calendar,
+            &format!("DESCRIPTION:{}", escape_text(description.trim_start())),
+        );
+        write_line(&mut calendar, "END:VEVENT");
+    }
+    write_line(&mut calendar, "END:VCALENDAR");
+
+    Ok(([(CONTENT_TYPE, "text/calendar; charset=utf-8")], calendar).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_text_escapes_special_characters() {
+        assert_eq!(escape_text("a,b;c"), r"a\,b\;c");
+        assert_eq!(escape_text(r"C:\labs"), r"C:\\labs");
+        assert_eq!(escape_text(r"\,"), r"\\\,");
+    }
+
+    #[test]
+    fn escape_text_escapes_every_line_ending() {
+        assert_eq!(escape_text("a\r\nb\nc\rd"), r"a\nb\nc\nd");
+        assert_eq!(escape_text("a\n\nb"), r"a\n\nb");
+    }
+
+    #[test]
+    fn write_line_leaves_short_lines_alone() {
+        let mut calendar = String::new();
+        let line = "a".repeat(MAXIMUM_LINE_LENGTH);
+        write_line(&mut calendar, &line);
+
+        assert_eq!(calendar, format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn write_line_folds_at_75_octets() {
+        let mut calendar = String::new();
+        write_line(&mut calendar, &"a".repeat(160));
+
+        // Continuation lines start with a space, which counts towards their length.
+        assert_eq!(
+            calendar,
+            format!(
+                "{}\r\n {}\r\n {}\r\n",
+                "a".repeat(75),
+                "a".repeat(74),
+                "a".repeat(11)
+            )
+        );
+    }
+
+    #[test]
+    fn write_line_never_splits_characters() {
+        let mut calendar = String::new();
+        write_line(&mut calendar, &format!("{}é", "a".repeat(74)));
+
+        assert_eq!(calendar, format!("{}\r\n é\r\n", "a".repeat(74)));
+        assert!(calendar
+            .split("\r\n")
+            .all(|line| line.len() <= MAXIMUM_LINE_LENGTH));
+    }
+}
@@ -1,3 +1,7 @@
 pub(crate) mod auth;
 pub(crate) mod block;
+pub(crate) mod calendar;
+pub(crate) mod scale;
+pub(crate) mod template;
 pub(crate) mod users;
+pub(crate) mod view;
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use cuid2::cuid;
+use diesel::{insert_into, RunQueryDsl};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::errors::AppResult;
+use crate::models::GradingScale;
+use crate::routes::api::auth::callback::Session;
+use crate::routes::api::scale::{parse_grade_map, validate_scale_name};
+use crate::schema::grading_scale::dsl::grading_scale;
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGradingScale {
+    pub name: String,
+    pub grade_map: serde_json::Value,
+}
+
+pub async fn create_grading_scale(
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(scale_data): Json<CreateGradingScale>,
+) -> AppResult<Json<GradingScale>> {
+    validate_scale_name(&scale_data.name)?;
+    let new_grade_map = parse_grade_map(&scale_data.grade_map)?;
+
+    let con = &mut state.get_db_con()?;
+    let scale = GradingScale {
+        id: cuid(),
+        user_id: Some(session.id.clone()),
+        name: scale_data.name.trim().to_string(),
+        grade_map: new_grade_map,
+        created_at: OffsetDateTime::now_utc(),
+    };
+    insert_into(grading_scale).values(&scale).execute(con)?;
+
+    Ok(Json(scale))
+}
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+
+use crate::errors::AppResult;
+use crate::models::GradingScale;
+use crate::routes::api::auth::callback::Session;
+use crate::schema::grading_scale::dsl::grading_scale;
+use crate::schema::grading_scale::{created_at, user_id};
+use crate::ServerState;
+
+/// Lists the preset grading scales, followed by the user's own scales.
+pub async fn get_grading_scales(
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<Vec<GradingScale>>> {
+    let con = &mut state.get_db_con()?;
+
+    let scales = grading_scale
+        .filter(user_id.eq(&session.id).or(user_id.is_null()))
+        .order((user_id.is_not_null(), created_at.asc()))
+        .select(GradingScale::as_select())
+        .load(con)?;
+
+    Ok(Json(scales))
+}
@@ -0,0 +1,46 @@
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+
+use crate::errors::{AppError, AppResult};
+use crate::grade_map::GradeMap;
+use crate::models::GradingScale;
+use crate::schema::grading_scale::dsl::grading_scale;
+use crate::schema::grading_scale::{id, user_id};
+
+pub(crate) mod create;
+pub(crate) mod list;
+pub(crate) mod scale_id;
+
+/// The longest name a grading scale can have.
+const MAXIMUM_NAME_LENGTH: usize = 191;
+
+pub(crate) fn validate_scale_name(name: &str) -> AppResult<()> {
+    if name.trim().is_empty() {
+        return AppError::bad_request("Grading scales must have a name.").into();
+    }
+    if name.chars().count() > MAXIMUM_NAME_LENGTH {
+        return AppError::bad_request("Grading scale names must be 191 characters or fewer.")
+            .into();
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_grade_map(value: &serde_json::Value) -> AppResult<GradeMap> {
+    GradeMap::parse(value).map_err(AppError::bad_request)
+}
+
+/// Finds a grading scale that the user can use, which is either one of their own
+/// or one of the presets.
+pub(crate) fn find_usable_scale(
+    con: &mut PgConnection,
+    _scale_id: &str,
+    _user_id: &str,
+) -> AppResult<GradingScale> {
+    grading_scale
+        .filter(id.eq(_scale_id))
+        .filter(user_id.eq(_user_id).or(user_id.is_null()))
+        .select(GradingScale::as_select())
+        .first(con)
+        .or_else(|_| AppError::resource_not_found().into())
+}
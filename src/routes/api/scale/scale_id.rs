@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use diesel::{
+    delete, update, AsChangeset, BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl,
+};
+use serde::Deserialize;
+
+use crate::errors::{AppError, AppResult};
+use crate::grade_map::GradeMap;
+use crate::models::GradingScale;
+use crate::routes::api::auth::callback::Session;
+use crate::routes::api::scale::{find_usable_scale, parse_grade_map, validate_scale_name};
+use crate::schema::grading_scale::dsl::grading_scale;
+use crate::schema::grading_scale::{id, user_id};
+use crate::ServerState;
+
+pub async fn get_grading_scale(
+    Path(_scale_id): Path<String>,
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<GradingScale>> {
+    let con = &mut state.get_db_con()?;
+
+    Ok(Json(find_usable_scale(con, &_scale_id, &session.id)?))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateGradingScaleRequest {
+    pub name: Option<String>,
+    pub grade_map: Option<serde_json::Value>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::grading_scale)]
+struct UpdateGradingScale {
+    name: Option<String>,
+    grade_map: Option<GradeMap>,
+}
+
+/// Updates one of the user's own grading scales. Preset scales can't be changed.
+pub async fn update_grading_scale(
+    Path(_scale_id): Path<String>,
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(scale_data): Json<UpdateGradingScaleRequest>,
+) -> AppResult<Json<GradingScale>> {
+    if let Some(new_name) = &scale_data.name {
+        validate_scale_name(new_name)?;
+    }
+    let changes = UpdateGradingScale {
+        name: scale_data.name.map(|n| n.trim().to_string()),
+        grade_map: scale_data
+            .grade_map
+            .as_ref()
+            .map(parse_grade_map)
+            .transpose()?,
+    };
+    if changes.name.is_none() && changes.grade_map.is_none() {
+        return AppError::bad_request("Must specify a name or a grade map to update.").into();
+    }
+
+    let con = &mut state.get_db_con()?;
+    let result = update(grading_scale.filter(id.eq(&_scale_id).and(user_id.eq(&session.id))))
+        .set(changes)
+        .execute(con)?;
+    if result != 1 {
+        return AppError::resource_not_found().into();
+    }
+
+    Ok(Json(find_usable_scale(con, &_scale_id, &session.id)?))
+}
+
+/// Deletes one of the user's own grading scales. Courses using it fall back to the
+/// user's own grade map.
+pub async fn delete_grading_scale(
+    Path(_scale_id): Path<String>,
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Response> {
+    let con = &mut state.get_db_con()?;
+
+    let result = delete(grading_scale.filter(id.eq(&_scale_id).and(user_id.eq(&session.id))))
+        .execute(con)?;
+
+    (result == 1)
+        .then(|| StatusCode::OK.into_response())
+        .ok_or(AppError::resource_not_found())
+}
@@ -0,0 +1,109 @@
+use bigdecimal::{BigDecimal, One, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{CourseComponent, CourseSubcomponent};
+
+pub(crate) mod search;
+pub(crate) mod template_id;
+
+/// The structure of a published course, stored as JSON on the template.
+/// Deliberately excludes anything that could identify the publisher's grades.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateComponent {
+    pub name: String,
+    pub name_of_subcomponent_singular: String,
+    pub subject_weighting: BigDecimal,
+    #[serde(rename = "numberOfSubComponentsToDrop_Lowest")]
+    pub number_of_subcomponents_to_drop_lowest: i32,
+    pub sequence_number: Option<i16>,
+    #[serde(default)]
+    pub hurdle: Option<BigDecimal>,
+    #[serde(default)]
+    pub is_bonus: bool,
+    #[serde(default = "BigDecimal::one")]
+    pub maximum_score: BigDecimal,
+    #[serde(default)]
+    pub late_penalty_per_day: Option<BigDecimal>,
+    #[serde(default)]
+    pub late_penalty_cap: Option<BigDecimal>,
+    pub subcomponents: Vec<TemplateSubcomponent>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSubcomponent {
+    pub number_in_sequence: i32,
+    pub override_name: Option<String>,
+    #[serde(default)]
+    pub weighting: Option<BigDecimal>,
+}
+
+impl TemplateComponent {
+    pub fn from_structure(
+        component: CourseComponent,
+        subcomponents: Vec<CourseSubcomponent>,
+    ) -> TemplateComponent {
+        TemplateComponent {
+            name: component.name,
+            name_of_subcomponent_singular: component.name_of_subcomponent_singular,
+            subject_weighting: component.subject_weighting,
+            number_of_subcomponents_to_drop_lowest: component
+                .number_of_subcomponents_to_drop_lowest,
+            sequence_number: component.sequence_number,
+            hurdle: component.hurdle,
+            is_bonus: component.is_bonus,
+            maximum_score: component.maximum_score,
+            late_penalty_per_day: component.late_penalty_per_day,
+            late_penalty_cap: component.late_penalty_cap,
+            subcomponents: subcomponents
+                .into_iter()
+                .map(|s| TemplateSubcomponent {
+                    number_in_sequence: s.number_in_sequence,
+                    override_name: s.override_name,
+                    weighting: s.weighting,
+                })
+                .collect(),
+        }
+    }
+
+    /// Converts the template back into an ungraded component and subcomponents.
+    /// IDs are left empty, and must be assigned before inserting.
+    pub fn into_structure(self) -> (CourseComponent, Vec<CourseSubcomponent>) {
+        (
+            CourseComponent {
+                id: "".to_string(),
+                name: self.name,
+                name_of_subcomponent_singular: self.name_of_subcomponent_singular,
+                number_of_subcomponents_to_drop_lowest: self.number_of_subcomponents_to_drop_lowest,
+                course_id: "".to_string(),
+                subject_weighting: self.subject_weighting,
+                sequence_number: self.sequence_number,
+                hurdle: self.hurdle,
+                is_bonus: self.is_bonus,
+                maximum_score: self.maximum_score,
+                late_penalty_per_day: self.late_penalty_per_day,
+                late_penalty_cap: self.late_penalty_cap,
+                scaling: None,
+                is_scaling_enabled: true,
+            },
+            self.subcomponents
+                .into_iter()
+                .map(|s| CourseSubcomponent {
+                    id: "".to_string(),
+                    component_id: "".to_string(),
+                    grade_value_percentage: BigDecimal::zero(),
+                    is_completed: false,
+                    number_in_sequence: s.number_in_sequence,
+                    override_name: s.override_name,
+                    points_earned: None,
+                    points_possible: None,
+                    weighting: s.weighting,
+                    due_at: None,
+                    submitted_at: None,
+                    extended_due_at: None,
+                })
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use axum::extract::Query;
+use axum::{Extension, Json};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, PgTextExpressionMethods, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+};
+use serde::Deserialize;
+
+use crate::errors::AppResult;
+use crate::models::CourseTemplate;
+use crate::schema::course_template::dsl::course_template;
+use crate::schema::course_template::{
+    course_code_name, course_code_number, created_at, institution, long_name, name,
+};
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchTemplates {
+    pub query: Option<String>,
+    pub institution: Option<String>,
+    #[serde(rename = "codeName")]
+    pub course_code_name: Option<String>,
+    #[serde(rename = "codeNo")]
+    pub course_code_number: Option<String>,
+}
+
+/// Escapes text so that it matches itself literally in a `LIKE` pattern.
+fn escape_pattern(text: &str) -> String {
+    text.trim()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn contains_pattern(text: &str) -> String {
+    format!("%{}%", escape_pattern(text))
+}
+
+pub async fn search_templates(
+    Extension(state): Extension<Arc<ServerState>>,
+    Query(search): Query<SearchTemplates>,
+) -> AppResult<Json<Vec<CourseTemplate>>> {
+    let con = &mut state.get_db_con()?;
+
+    let mut query = course_template
+        .select(CourseTemplate::as_select())
+        .order(created_at.desc())
+        .limit(50)
+        .into_boxed();
+    if let Some(text) = &search.query {
+        let pattern = contains_pattern(text);
+        query = query.filter(name.ilike(pattern.clone()).or(long_name.ilike(pattern)));
+    }
+    if let Some(text) = &search.institution {
+        query = query.filter(institution.ilike(contains_pattern(text)));
+    }
+    if let Some(code_name) = &search.course_code_name {
+        query = query.filter(course_code_name.ilike(escape_pattern(code_name)));
+    }
+    if let Some(code_number) = &search.course_code_number {
+        query = query.filter(course_code_number.eq(code_number.trim()));
+    }
+
+    Ok(Json(query.load(con)?))
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use diesel::{
+    delete, BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+
+use crate::errors::{AppError, AppResult};
+use crate::models::CourseTemplate;
+use crate::routes::api::auth::callback::Session;
+use crate::schema::course_template::dsl::course_template;
+use crate::schema::course_template::{id, user_id};
+use crate::ServerState;
+
+pub async fn get_template(
+    Path(_template_id): Path<String>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<CourseTemplate>> {
+    let con = &mut state.get_db_con()?;
+
+    let template = course_template
+        .find(&_template_id)
+        .select(CourseTemplate::as_select())
+        .first(con)?;
+
+    Ok(Json(template))
+}
+
+pub async fn delete_template(
+    Path(_template_id): Path<String>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+) -> AppResult<Response> {
+    let con = &mut state.get_db_con()?;
+
+    let result = delete(course_template.filter(id.eq(&_template_id).and(user_id.eq(&session.id))))
+        .execute(con)?;
+
+    (result == 1)
+        .then(|| StatusCode::OK.into_response())
+        .ok_or(AppError::resource_not_found())
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use diesel::{BelongingToDsl, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::errors::AppResult;
+use crate::grading::{GradeSummary, TargetProgress};
+use crate::models::{Course, StudyBlock, User};
+use crate::routes::api::auth::callback::Session;
+use crate::routes::api::users::gpa::is_finished;
+use crate::routes::api::users::me::load_study_blocks;
+use crate::schema::gk_user::dsl::gk_user;
+use crate::schema::study_block::{end_date, start_date};
+use crate::ServerState;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAtRiskCourse {
+    #[serde(flatten)]
+    pub course: Course,
+    pub study_block_name: String,
+    pub grade: GradeSummary,
+    pub target_progress: TargetProgress,
+}
+
+/// Lists every unfinished course, in study blocks that haven't ended yet, whose target is at
+/// risk or can no longer be reached, so that clients can warn the user about them.
+pub async fn get_at_risk_courses(
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<Vec<GetAtRiskCourse>>> {
+    let con = &mut state.get_db_con()?;
+
+    let user = gk_user
+        .find(&session.id)
+        .select(User::as_select())
+        .first(con)?;
+    let study_blocks = StudyBlock::belonging_to(&user)
+        .filter(end_date.gt(OffsetDateTime::now_utc()))
+        .order(start_date.asc())
+        .select(StudyBlock::as_select())
+        .load(con)?;
+
+    let at_risk_courses = load_study_blocks(con, study_blocks, &user.grade_map)?
+        .into_iter()
+        .flat_map(|block| {
+            let study_block_name = block.study_block.name;
+            block.courses.into_iter().filter_map(move |c| {
+                if is_finished(&c) {
+                    return None;
+                }
+                let target_progress = c.target_progress.filter(|t| t.status.is_at_risk())?;
+                Some(GetAtRiskCourse {
+                    course: c.course,
+                    study_block_name: study_block_name.clone(),
+                    grade: c.grade,
+                    target_progress,
+                })
+            })
+        })
+        .collect();
+
+    Ok(Json(at_risk_courses))
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use cuid2::CuidConstructor;
+use diesel::SelectableHelper;
+use diesel::{delete, insert_into, Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use time::OffsetDateTime;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::CalendarFeed;
+use crate::routes::api::auth::callback::Session;
+use crate::schema::calendar_feed::dsl::calendar_feed;
+use crate::schema::calendar_feed::user_id;
+use crate::ServerState;
+
+const FEED_TOKEN_GENERATOR: CuidConstructor = CuidConstructor::new().with_length(32);
+
+pub async fn get_calendar_feed(
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+) -> AppResult<Json<CalendarFeed>> {
+    let con = &mut state.get_db_con()?;
+
+    let feed = calendar_feed
+        .find(&session.id)
+        .select(CalendarFeed::as_select())
+        .first(con)?;
+
+    Ok(Json(feed))
+}
+
+/// Issues a new calendar feed token, replacing the user's existing one, if any.
+/// Calendar apps subscribed with the old token will stop receiving updates.
+pub async fn rotate_calendar_feed(
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+) -> AppResult<Json<CalendarFeed>> {
+    let con = &mut state.get_db_con()?;
+
+    let feed = CalendarFeed {
+        user_id: session.id.clone(),
+        token: FEED_TOKEN_GENERATOR.create_id(),
+        created_at: OffsetDateTime::now_utc(),
+    };
+    con.transaction(|txn| {
+        delete(calendar_feed.filter(user_id.eq(&session.id))).execute(txn)?;
+        insert_into(calendar_feed).values(&feed).execute(txn)
+    })?;
+
+    Ok(Json(feed))
+}
+
+pub async fn delete_calendar_feed(
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+) -> AppResult<Response> {
+    let con = &mut state.get_db_con()?;
+
+    let result = delete(calendar_feed.filter(user_id.eq(&session.id))).execute(con)?;
+
+    (result == 1)
+        .then(|| StatusCode::OK.into_response())
+        .ok_or(AppError::resource_not_found())
+}
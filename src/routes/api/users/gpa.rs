@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use axum::extract::Query;
+use axum::{Extension, Json};
+use bigdecimal::{BigDecimal, Zero};
+use diesel::{BelongingToDsl, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppResult;
+use crate::grade_map::GradeMap;
+use crate::grading::weighted_gpa;
+use crate::models::{StudyBlock, User};
+use crate::routes::api::auth::callback::Session;
+use crate::routes::api::users::me::{load_study_blocks, GetUserCourse};
+use crate::schema::gk_user::dsl::gk_user;
+use crate::schema::study_block::start_date;
+use crate::ServerState;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpaQuery {
+    /// Whether to include unfinished courses, using their projected grades.
+    #[serde(default)]
+    pub projected: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetGpa {
+    pub blocks: Vec<GetBlockGpa>,
+    pub cumulative: GpaSummary,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpaSummary {
+    /// The GPA, or `None` if no counted courses are worth any credits.
+    pub gpa: Option<BigDecimal>,
+    /// The total credits of every counted course.
+    pub credits: BigDecimal,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBlockGpa {
+    pub study_block_id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub summary: GpaSummary,
+    pub courses: Vec<GetCourseGpa>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCourseGpa {
+    pub course_id: String,
+    pub percentage: Option<BigDecimal>,
+    pub grade: Option<String>,
+    pub gpa_points: Option<BigDecimal>,
+    pub credits: BigDecimal,
+    /// Whether the course counts towards the GPA, which requires a grade worth GPA points
+    /// (or a percentage below every grade, in a grade map that awards GPA points).
+    pub counted: bool,
+}
+
+impl GetCourseGpa {
+    fn new(course: &GetUserCourse, user_grade_map: &GradeMap, projected: bool) -> GetCourseGpa {
+        let percentage = if projected {
+            course.grade.projected.clone()
+        } else {
+            is_finished(course).then(|| course.grade.actual.clone())
+        };
+        let grade_map = course.grade_map.as_ref().unwrap_or(user_grade_map);
+        let entry = percentage.as_ref().and_then(|p| grade_map.entry_for(p));
+        // A percentage below every threshold is a fail, which is worth no GPA points,
+        // rather than leaving the course out of the GPA.
+        let gpa_points = match (&percentage, entry) {
+            (_, Some(e)) => e.gpa_points.clone(),
+            (Some(_), None) if grade_map.entries().iter().any(|e| e.gpa_points.is_some()) => {
+                Some(BigDecimal::zero())
+            }
+            _ => None,
+        };
+        GetCourseGpa {
+            course_id: course.course.id.clone(),
+            grade: entry.map(|e| e.label.clone()),
+            counted: gpa_points.is_some() && !course.course.credits.is_zero(),
+            gpa_points,
+            percentage,
+            credits: course.course.credits.clone(),
+        }
+    }
+}
+
+/// A course is finished once it has at least one subcomponent, and every subcomponent is completed.
+pub(crate) fn is_finished(course: &GetUserCourse) -> bool {
+    let mut subcomponents = course
+        .components
+        .iter()
+        .flat_map(|c| c.subcomponents.iter())
+        .peekable();
+    subcomponents.peek().is_some() && subcomponents.all(|s| s.is_completed)
+}
+
+fn summarise<'a>(courses: impl IntoIterator<Item = &'a GetCourseGpa>) -> GpaSummary {
+    let counted = courses
+        .into_iter()
+        .filter(|c| c.counted)
+        .collect::<Vec<_>>();
+    GpaSummary {
+        gpa: weighted_gpa(
+            counted
+                .iter()
+                .filter_map(|c| c.gpa_points.as_ref().map(|p| (p, &c.credits))),
+        ),
+        credits: counted.iter().map(|c| &c.credits).sum(),
+    }
+}
+
+/// Computes the user's GPA in each study block, and cumulatively across all of them.
+/// Only finished courses are counted, unless projected grades are requested.
+pub async fn get_gpa(
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Query(query): Query<GpaQuery>,
+) -> AppResult<Json<GetGpa>> {
+    let con = &mut state.get_db_con()?;
+
+    let user = gk_user
+        .find(&session.id)
+        .select(User::as_select())
+        .first(con)?;
+    let study_blocks = StudyBlock::belonging_to(&user)
+        .order(start_date.asc())
+        .select(StudyBlock::as_select())
+        .load(con)?;
+
+    let blocks = load_study_blocks(con, study_blocks, &user.grade_map)?
+        .into_iter()
+        .map(|block| {
+            let courses = block
+                .courses
+                .iter()
+                .map(|c| GetCourseGpa::new(c, &user.grade_map, query.projected))
+                .collect::<Vec<GetCourseGpa>>();
+            GetBlockGpa {
+                study_block_id: block.study_block.id,
+                name: block.study_block.name,
+                summary: summarise(&courses),
+                courses,
+            }
+        })
+        .collect::<Vec<GetBlockGpa>>();
+
+    Ok(Json(GetGpa {
+        cumulative: summarise(blocks.iter().flat_map(|b| b.courses.iter())),
+        blocks,
+    }))
+}
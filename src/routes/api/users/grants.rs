@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use cuid2::{cuid, CuidConstructor};
+use diesel::{
+    delete, insert_into, BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::errors::{AppError, AppResult};
+use crate::models::ViewGrant;
+use crate::routes::api::auth::callback::Session;
+use crate::schema::view_grant::dsl::view_grant;
+use crate::schema::view_grant::{created_at, id, user_id};
+use crate::ServerState;
+
+const VIEW_GRANT_TOKEN_GENERATOR: CuidConstructor = CuidConstructor::new().with_length(32);
+
+/// The longest label a link can have.
+const MAXIMUM_LABEL_LENGTH: usize = 191;
+
+#[derive(Deserialize)]
+pub struct ViewGrantScope {
+    pub block_id: String,
+    pub course_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateViewGrant {
+    #[serde(default)]
+    pub label: String,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+/// Issues a read-only link to a study block, or to a single course if the route has a course ID.
+pub async fn create_view_grant(
+    Path(scope): Path<ViewGrantScope>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+    Json(grant_data): Json<CreateViewGrant>,
+) -> AppResult<Json<ViewGrant>> {
+    let now = OffsetDateTime::now_utc();
+    if grant_data.expires_at.is_some_and(|expiry| expiry <= now) {
+        return AppError::bad_request("Links must expire in the future.").into();
+    }
+    if grant_data.label.chars().count() > MAXIMUM_LABEL_LENGTH {
+        return AppError::bad_request("Link labels must be 191 characters or fewer.").into();
+    }
+
+    let con = &mut state.get_db_con()?;
+    let grant = ViewGrant {
+        id: cuid(),
+        token: VIEW_GRANT_TOKEN_GENERATOR.create_id(),
+        user_id: session.id.clone(),
+        block_id: scope.block_id,
+        course_id: scope.course_id,
+        label: grant_data.label,
+        created_at: now,
+        expires_at: grant_data.expires_at,
+    };
+    insert_into(view_grant).values(&grant).execute(con)?;
+
+    Ok(Json(grant))
+}
+
+pub async fn get_view_grants(
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+) -> AppResult<Json<Vec<ViewGrant>>> {
+    let con = &mut state.get_db_con()?;
+
+    let grants = view_grant
+        .filter(user_id.eq(&session.id))
+        .order(created_at.desc())
+        .select(ViewGrant::as_select())
+        .load(con)?;
+
+    Ok(Json(grants))
+}
+
+pub async fn revoke_view_grant(
+    Path(_grant_id): Path<String>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Extension(session): Extension<Arc<Session>>,
+) -> AppResult<Response> {
+    let con = &mut state.get_db_con()?;
+
+    let result =
+        delete(view_grant.filter(id.eq(&_grant_id).and(user_id.eq(&session.id)))).execute(con)?;
+
+    (result == 1)
+        .then(|| StatusCode::OK.into_response())
+        .ok_or(AppError::resource_not_found())
+}
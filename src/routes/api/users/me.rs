@@ -1,24 +1,33 @@
 use std::sync::Arc;
 
 use crate::errors::AppError;
-use crate::models::{Course, CourseComponent, CourseSubcomponent, StudyBlock, User};
+use crate::grade_map::GradeMap;
+use crate::grading::{
+    course_hurdles, grade_component, grade_course, late_penalty, target_progress, CourseHurdles,
+    GradeSummary, LatePenalty, TargetProgress,
+};
+use crate::models::{
+    Course, CourseComponent, CourseSubcomponent, GradingScale, Scenario, ScenarioSubcomponent,
+    StudyBlock, User,
+};
 use crate::routes::api::auth::callback::Session;
 use crate::routes::api::users::{gather_meta_info, ServerMetaInfo};
 use crate::schema::gk_user::dsl::gk_user;
 use crate::schema::gk_user::{grade_map, id};
+use crate::schema::grading_scale::dsl::grading_scale;
 use crate::ServerState;
 use axum::{Extension, Json};
+use bigdecimal::BigDecimal;
 use diesel::prelude::*;
 use diesel::{delete, insert_into, update};
 use hyper::StatusCode;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use time::OffsetDateTime;
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetUser {
-    grade_map: serde_json::Value,
+    grade_map: GradeMap,
     study_blocks: Vec<GetUserStudyBlock>,
     meta: ServerMetaInfo,
 }
@@ -27,8 +36,8 @@ pub struct GetUser {
 #[serde(rename_all = "camelCase")]
 pub struct GetUserStudyBlock {
     #[serde(flatten)]
-    study_block: StudyBlock,
-    courses: Vec<GetUserCourse>,
+    pub study_block: StudyBlock,
+    pub courses: Vec<GetUserCourse>,
 }
 
 //#[derive(Serialize, ToSchema)]
@@ -39,6 +48,145 @@ pub struct GetUserCourse {
     pub course: Course,
 
     pub components: Vec<GetUserComponent>,
+    pub grade: GradeSummary,
+    pub scenarios: Vec<GetScenario>,
+    /// The grade map of the course's grading scale, which overrides the user's own grade map.
+    pub grade_map: Option<GradeMap>,
+    /// The status of each hurdle in the course, or `None` if the course doesn't have any.
+    pub hurdles: Option<CourseHurdles>,
+    /// Whether the course's target is secured, achievable or at risk, or `None` if the course
+    /// doesn't have a target.
+    pub target_progress: Option<TargetProgress>,
+}
+
+impl GetUserCourse {
+    pub fn new(
+        course: Course,
+        components: Vec<GetUserComponent>,
+        scenarios: Vec<(Scenario, Vec<ScenarioSubcomponent>)>,
+        scale: Option<GradingScale>,
+        user_grade_map: &GradeMap,
+    ) -> GetUserCourse {
+        // Targets set as a grade follow that grade's current threshold, in case the grade map
+        // has changed since the target was set.
+        let target = course
+            .target_grade
+            .as_ref()
+            .and_then(|g| {
+                scale
+                    .as_ref()
+                    .map(|s| &s.grade_map)
+                    .unwrap_or(user_grade_map)
+                    .threshold_for(g)
+                    .cloned()
+            })
+            .or(course.target);
+        let course = Course { target, ..course };
+        let grade = grade_course(
+            components
+                .iter()
+                .map(|c| (&c.component, c.subcomponents.as_slice())),
+        );
+        let hurdles = course_hurdles(
+            course.hurdle.as_ref(),
+            &grade,
+            components.iter().map(|c| (&c.component, &c.grade)),
+        );
+        let target_progress = course.target.as_ref().map(|target| {
+            target_progress(
+                target,
+                &grade,
+                &components
+                    .iter()
+                    .map(|c| (&c.component, c.subcomponents.as_slice()))
+                    .collect::<Vec<_>>(),
+            )
+        });
+        let scenarios = scenarios
+            .into_iter()
+            .map(|(scenario, scores)| {
+                GetScenario::new(scenario, scores, course.hurdle.as_ref(), &components)
+            })
+            .collect();
+        GetUserCourse {
+            course,
+            components,
+            grade,
+            scenarios,
+            grade_map: scale.map(|s| s.grade_map),
+            hurdles,
+            target_progress,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetScenario {
+    #[serde(flatten)]
+    pub scenario: Scenario,
+
+    pub scores: Vec<ScenarioSubcomponent>,
+    pub grade: GradeSummary,
+    pub hurdles: Option<CourseHurdles>,
+}
+
+impl GetScenario {
+    /// Grades the course as if each subcomponent in the scenario had been completed
+    /// with its hypothetical score.
+    pub fn new(
+        scenario: Scenario,
+        scores: Vec<ScenarioSubcomponent>,
+        course_hurdle: Option<&BigDecimal>,
+        components: &[GetUserComponent],
+    ) -> GetScenario {
+        let hypothetical_components = components
+            .iter()
+            .map(|c| {
+                let subcomponents = c
+                    .subcomponents
+                    .iter()
+                    .map(
+                        |subc| match scores.iter().find(|s| s.subcomponent_id == subc.id) {
+                            Some(score) => CourseSubcomponent {
+                                grade_value_percentage: score.grade_value_percentage.clone(),
+                                is_completed: true,
+                                points_earned: None,
+                                points_possible: None,
+                                ..subc.clone()
+                            },
+                            None => subc.clone(),
+                        },
+                    )
+                    .collect::<Vec<CourseSubcomponent>>();
+                (&c.component, subcomponents)
+            })
+            .collect::<Vec<(&CourseComponent, Vec<CourseSubcomponent>)>>();
+        let grade = grade_course(
+            hypothetical_components
+                .iter()
+                .map(|(component, subcomponents)| (*component, subcomponents.as_slice())),
+        );
+        let component_grades = hypothetical_components
+            .iter()
+            .map(|(component, subcomponents)| {
+                (*component, grade_component(component, subcomponents))
+            })
+            .collect::<Vec<(&CourseComponent, GradeSummary)>>();
+        let hurdles = course_hurdles(
+            course_hurdle,
+            &grade,
+            component_grades
+                .iter()
+                .map(|(component, grade)| (*component, grade)),
+        );
+        GetScenario {
+            scenario,
+            scores,
+            grade,
+            hurdles,
+        }
+    }
 }
 
 //#[derive(Serialize, ToSchema)]
@@ -49,6 +197,110 @@ pub struct GetUserComponent {
     pub component: CourseComponent,
 
     pub subcomponents: Vec<CourseSubcomponent>,
+    pub grade: GradeSummary,
+    /// The penalties applied to subcomponents that were submitted late. Each subcomponent's
+    /// `gradeValuePercentage` is its raw score, before the penalty.
+    pub late_penalties: Vec<LatePenalty>,
+}
+
+impl GetUserComponent {
+    pub fn new(
+        component: CourseComponent,
+        subcomponents: Vec<CourseSubcomponent>,
+    ) -> GetUserComponent {
+        let grade = grade_component(&component, &subcomponents);
+        let late_penalties = subcomponents
+            .iter()
+            .filter_map(|s| late_penalty(&component, s))
+            .collect();
+        GetUserComponent {
+            component,
+            subcomponents,
+            grade,
+            late_penalties,
+        }
+    }
+}
+
+/// Loads every course, component, subcomponent and scenario in the given study blocks.
+/// `user_grade_map` is the grade map of the user who owns the study blocks.
+pub fn load_study_blocks(
+    con: &mut PgConnection,
+    study_blocks: Vec<StudyBlock>,
+    user_grade_map: &GradeMap,
+) -> Result<Vec<GetUserStudyBlock>, AppError> {
+    let courses = Course::belonging_to(&study_blocks)
+        .select(Course::as_select())
+        .load(con)?;
+    let components = CourseComponent::belonging_to(&courses)
+        .select(CourseComponent::as_select())
+        .load(con)?;
+    let subcomponents = CourseSubcomponent::belonging_to(&components)
+        .select(CourseSubcomponent::as_select())
+        .load(con)?;
+    let scenarios = Scenario::belonging_to(&courses)
+        .select(Scenario::as_select())
+        .load(con)?;
+    let scenario_scores = ScenarioSubcomponent::belonging_to(&scenarios)
+        .select(ScenarioSubcomponent::as_select())
+        .load(con)?;
+    let scales = grading_scale
+        .filter(
+            crate::schema::grading_scale::id
+                .eq_any(courses.iter().filter_map(|c| c.grading_scale_id.clone())),
+        )
+        .select(GradingScale::as_select())
+        .load(con)?;
+
+    Ok(study_blocks
+        .into_iter()
+        .map(|s| GetUserStudyBlock {
+            study_block: s.clone(),
+            courses: courses
+                .clone()
+                .into_iter()
+                .filter(|x| x.block_id == s.id)
+                .map(|c| {
+                    GetUserCourse::new(
+                        c.clone(),
+                        components
+                            .clone()
+                            .into_iter()
+                            .filter(|component| component.course_id == c.id)
+                            .map(|component| {
+                                GetUserComponent::new(
+                                    component.clone(),
+                                    subcomponents
+                                        .clone()
+                                        .into_iter()
+                                        .filter(|subc| subc.component_id == component.id)
+                                        .collect(),
+                                )
+                            })
+                            .collect(),
+                        scenarios
+                            .clone()
+                            .into_iter()
+                            .filter(|scenario| scenario.course_id == c.id)
+                            .map(|scenario| {
+                                let scores = scenario_scores
+                                    .clone()
+                                    .into_iter()
+                                    .filter(|score| score.scenario_id == scenario.id)
+                                    .collect();
+                                (scenario, scores)
+                            })
+                            .collect(),
+                        scales
+                            .iter()
+                            .find(|scale| c.grading_scale_id.as_ref() == Some(&scale.id))
+                            .cloned(),
+                        user_grade_map,
+                    )
+                })
+                .collect(),
+        })
+        .collect())
 }
 
 pub async fn get_user<B>(
@@ -67,63 +319,17 @@ pub async fn get_user<B>(
             let study_blocks = StudyBlock::belonging_to(&user)
                 .select(StudyBlock::as_select())
                 .load(con)?;
-            let courses = Course::belonging_to(&study_blocks)
-                .select(Course::as_select())
-                .load(con)?;
-            let components = CourseComponent::belonging_to(&courses)
-                .select(CourseComponent::as_select())
-                .load(con)?;
-            let subcomponents = CourseSubcomponent::belonging_to(&components)
-                .select(CourseSubcomponent::as_select())
-                .load(con)?;
 
             Ok(Json(GetUser {
+                study_blocks: load_study_blocks(con, study_blocks, &user.grade_map)?,
                 grade_map: user.grade_map,
-                study_blocks: study_blocks
-                    .into_iter()
-                    .map(|s| GetUserStudyBlock {
-                        study_block: s.clone(),
-                        courses: courses
-                            .clone()
-                            .into_iter()
-                            .filter(|x| x.block_id == s.id)
-                            .map(|c| GetUserCourse {
-                                course: c.clone(),
-                                components: components
-                                    .clone()
-                                    .into_iter()
-                                    .filter(|component| component.course_id == c.id)
-                                    .map(|component| GetUserComponent {
-                                        component: component.clone(),
-                                        subcomponents: subcomponents
-                                            .clone()
-                                            .into_iter()
-                                            .filter(|subc| subc.component_id == component.id)
-                                            .collect(),
-                                    })
-                                    .collect(),
-                            })
-                            .collect(),
-                    })
-                    .collect(),
                 meta: gather_meta_info(),
             }))
         }
         Err(diesel::NotFound) => {
             let user = User {
                 id: user_session.id.clone(),
-                grade_map: json!({
-                  "0.4": "D",
-                  "0.5": "C-",
-                  "0.6": "C+",
-                  "0.7": "B",
-                  "0.8": "A-",
-                  "0.9": "A+",
-                  "0.55": "C",
-                  "0.65": "B-",
-                  "0.75": "B+",
-                  "0.85": "A",
-                }),
+                grade_map: GradeMap::default(),
                 created_at: OffsetDateTime::now_utc(),
             };
             insert_into(gk_user).values(&user).execute(con)?;
@@ -148,10 +354,12 @@ pub async fn update_user(
     Extension(state): Extension<Arc<ServerState>>,
     Json(data): Json<UpdateUser>,
 ) -> Result<StatusCode, AppError> {
+    let new_grade_map = GradeMap::parse(&data.grade_map).map_err(AppError::bad_request)?;
+
     let con = &mut state.get_db_con()?;
 
     let result = update(gk_user.filter(id.eq(&user_session.id)))
-        .set(grade_map.eq(data.grade_map))
+        .set(grade_map.eq(new_grade_map))
         .execute(con)?;
 
     match result {
@@ -1,6 +1,11 @@
 use serde::Serialize;
 
+pub(crate) mod at_risk;
+pub(crate) mod feed;
+pub(crate) mod gpa;
+pub(crate) mod grants;
 pub(crate) mod me;
+pub(crate) mod upcoming;
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use axum::extract::Query;
+use axum::{Extension, Json};
+use diesel::sql_types::{Nullable, Timestamptz};
+use diesel::{define_sql_function, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{Course, CourseComponent, CourseSubcomponent};
+use crate::routes::api::auth::callback::Session;
+use crate::schema::course::dsl::course;
+use crate::schema::course_component::dsl::course_component;
+use crate::schema::course_subcomponent::dsl::course_subcomponent;
+use crate::schema::course_subcomponent::{due_at, extended_due_at, is_completed};
+use crate::schema::study_block::dsl::study_block;
+use crate::ServerState;
+
+const MAXIMUM_UPCOMING: i64 = 100;
+
+define_sql_function! {
+    fn coalesce(x: Nullable<Timestamptz>, y: Nullable<Timestamptz>) -> Nullable<Timestamptz>;
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpcomingQuery {
+    /// Only include subcomponents due within this many days. Overdue subcomponents are always
+    /// included while their study block is still running, since they haven't been completed yet.
+    pub days: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUpcomingSubcomponent {
+    #[serde(flatten)]
+    pub subcomponent: CourseSubcomponent,
+    pub component: CourseComponent,
+    pub course: Course,
+    pub study_block_name: String,
+    /// The extended due date if the subcomponent has an extension, otherwise its due date.
+    #[serde(with = "time::serde::rfc3339")]
+    pub effective_due_at: OffsetDateTime,
+}
+
+/// Lists every incomplete subcomponent with a due date, across all of the user's study blocks
+/// that haven't ended, ordered from the earliest due date, taking extensions into account.
+pub async fn get_upcoming(
+    Extension(session): Extension<Arc<Session>>,
+    Extension(state): Extension<Arc<ServerState>>,
+    Query(query): Query<UpcomingQuery>,
+) -> AppResult<Json<Vec<GetUpcomingSubcomponent>>> {
+    if query.days.is_some_and(|days| !(0..=366).contains(&days)) {
+        return AppError::bad_request("Days must be between 0 and 366.").into();
+    }
+    let con = &mut state.get_db_con()?;
+
+    let mut upcoming = course_subcomponent
+        .inner_join(course_component.inner_join(course.inner_join(study_block)))
+        .filter(crate::schema::study_block::user_id.eq(&session.id))
+        .filter(crate::schema::study_block::end_date.gt(OffsetDateTime::now_utc()))
+        .filter(is_completed.eq(false))
+        .filter(due_at.is_not_null())
+        .into_boxed();
+    if let Some(days) = query.days {
+        upcoming = upcoming.filter(
+            coalesce(extended_due_at, due_at).le(OffsetDateTime::now_utc() + Duration::days(days)),
+        );
+    }
+
+    let upcoming = upcoming
+        .order((
+            coalesce(extended_due_at, due_at).asc(),
+            crate::schema::course_subcomponent::id.asc(),
+        ))
+        .limit(MAXIMUM_UPCOMING)
+        .select((
+            CourseSubcomponent::as_select(),
+            CourseComponent::as_select(),
+            Course::as_select(),
+            crate::schema::study_block::name,
+        ))
+        .load::<(CourseSubcomponent, CourseComponent, Course, String)>(con)?
+        .into_iter()
+        .filter_map(
+            |(subcomponent, component, parent_course, study_block_name)| {
+                Some(GetUpcomingSubcomponent {
+                    effective_due_at: subcomponent.extended_due_at.or(subcomponent.due_at)?,
+                    subcomponent,
+                    component,
+                    course: parent_course,
+                    study_block_name,
+                })
+            },
+        )
+        .collect();
+
+    Ok(Json(upcoming))
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::{Extension, Json};
+use diesel::{QueryDsl, RunQueryDsl, SelectableHelper};
+use serde::Serialize;
+
+use crate::errors::{AppError, AppResult};
+use crate::grade_map::GradeMap;
+use crate::models::{StudyBlock, User, ViewGrant};
+use crate::routes::api::block::_block_id::course::course_id::get_course;
+use crate::routes::api::users::me::{load_study_blocks, GetUserCourse, GetUserStudyBlock};
+use crate::schema::gk_user::dsl::gk_user;
+use crate::schema::study_block::dsl::study_block;
+use crate::ServerState;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase", tag = "scope")]
+pub enum SharedView {
+    Block(GetUserStudyBlock),
+    Course(Box<GetUserCourse>),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSharedView {
+    label: String,
+    grade_map: GradeMap,
+    #[serde(flatten)]
+    view: SharedView,
+}
+
+/// Returns a read-only view of whatever a view grant link is scoped to.
+pub async fn get_shared_view(
+    Path(_grant_token): Path<String>,
+    Extension(grant): Extension<Arc<ViewGrant>>,
+    Extension(state): Extension<Arc<ServerState>>,
+) -> AppResult<Json<GetSharedView>> {
+    let con = &mut state.get_db_con()?;
+
+    let owner = gk_user
+        .find(&grant.user_id)
+        .select(User::as_select())
+        .first(con)?;
+
+    let view = match &grant.course_id {
+        Some(_course_id) => SharedView::Course(Box::new(
+            get_course(
+                Path((grant.block_id.clone(), _course_id.clone())),
+                Extension(state.clone()),
+            )
+            .await?
+            .0,
+        )),
+        None => {
+            let block = study_block
+                .find(&grant.block_id)
+                .select(StudyBlock::as_select())
+                .first(con)?;
+            SharedView::Block(
+                load_study_blocks(con, vec![block], &owner.grade_map)?
+                    .pop()
+                    .ok_or_else(AppError::resource_not_found)?,
+            )
+        }
+    };
+
+    Ok(Json(GetSharedView {
+        label: grant.label.clone(),
+        grade_map: owner.grade_map,
+        view,
+    }))
+}
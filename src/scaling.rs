@@ -0,0 +1,221 @@
+use bigdecimal::{BigDecimal, One, Zero};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Json;
+use serde::{Deserialize, Serialize};
+
+const MAXIMUM_POINTS: usize = 20;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScalingPoint {
+    pub raw: BigDecimal,
+    pub scaled: BigDecimal,
+}
+
+/// A rule for scaling the scores of every subcomponent in a component, such as when a
+/// lecturer adjusts the marks of a test after the fact.
+///
+/// Serialized as a JSON object tagged by `type`, e.g. `{"type": "additive", "amount": "0.08"}`.
+#[derive(Serialize, Deserialize, AsExpression, FromSqlRow, Clone, Debug, PartialEq)]
+#[diesel(sql_type = Json)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ScalingRule {
+    /// Adds `amount` to every score.
+    Additive { amount: BigDecimal },
+    /// Multiplies every score by `factor`.
+    Multiplicative { factor: BigDecimal },
+    /// Interpolates linearly between the points, which are sorted by raw score.
+    /// Scores outside of the points are shifted by the same amount as the nearest point.
+    PiecewiseLinear { points: Vec<ScalingPoint> },
+}
+
+impl ScalingRule {
+    /// Validates a rule. Additive amounts must be between -1 and 1, factors must be greater
+    /// than 0 and at most 5, and piecewise-linear mappings must have between 2 and 20 points
+    /// between 0 and 5, with strictly increasing raw scores and non-decreasing scaled scores,
+    /// so that scaling never changes which scores are lowest.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ScalingRule::Additive { amount } => {
+                if amount.abs() > BigDecimal::one() {
+                    return Err("Scaling amount must be between -100% and 100%.".to_string());
+                }
+            }
+            ScalingRule::Multiplicative { factor } => {
+                if *factor <= BigDecimal::zero() || *factor > BigDecimal::new(5.into(), 0) {
+                    return Err("Scaling factor must be greater than 0 and at most 5.".to_string());
+                }
+            }
+            ScalingRule::PiecewiseLinear { points } => {
+                if points.len() < 2 || points.len() > MAXIMUM_POINTS {
+                    return Err(format!(
+                        "Scaling must have between 2 and {} points.",
+                        MAXIMUM_POINTS
+                    ));
+                }
+                if points.iter().any(|p| {
+                    [&p.raw, &p.scaled]
+                        .iter()
+                        .any(|v| **v < BigDecimal::zero() || **v > BigDecimal::new(5.into(), 0))
+                }) {
+                    return Err("Scaling points must be between 0% and 500%.".to_string());
+                }
+                if points
+                    .windows(2)
+                    .any(|w| w[0].raw >= w[1].raw || w[0].scaled > w[1].scaled)
+                {
+                    return Err(
+                        "Scaling points must be in order, and scaled scores must not decrease."
+                            .to_string(),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Scales a raw score. The result isn't clamped to any range.
+    pub fn apply(&self, score: &BigDecimal) -> BigDecimal {
+        match self {
+            ScalingRule::Additive { amount } => score + amount,
+            ScalingRule::Multiplicative { factor } => score * factor,
+            ScalingRule::PiecewiseLinear { points } => {
+                let (Some(first), Some(last)) = (points.first(), points.last()) else {
+                    return score.clone();
+                };
+                if *score <= first.raw {
+                    return score + (&first.scaled - &first.raw);
+                }
+                if *score >= last.raw {
+                    return score + (&last.scaled - &last.raw);
+                }
+                let segment = points
+                    .windows(2)
+                    .find(|w| *score <= w[1].raw)
+                    .expect("Score is within the points");
+                let (low, high) = (&segment[0], &segment[1]);
+                &low.scaled
+                    + (score - &low.raw) * (&high.scaled - &low.scaled) / (&high.raw - &low.raw)
+            }
+        }
+    }
+}
+
+impl FromSql<Json, Pg> for ScalingRule {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let value = <serde_json::Value as FromSql<Json, Pg>>::from_sql(bytes)?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+impl ToSql<Json, Pg> for ScalingRule {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        serde_json::to_writer(out, self)?;
+        Ok(IsNull::No)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(value: &str) -> BigDecimal {
+        value.parse().unwrap()
+    }
+
+    fn point(raw: &str, scaled: &str) -> ScalingPoint {
+        ScalingPoint {
+            raw: decimal(raw),
+            scaled: decimal(scaled),
+        }
+    }
+
+    fn piecewise() -> ScalingRule {
+        ScalingRule::PiecewiseLinear {
+            points: vec![point("0.5", "0.6"), point("0.8", "0.85")],
+        }
+    }
+
+    #[test]
+    fn piecewise_maps_points_exactly() {
+        assert_eq!(piecewise().apply(&decimal("0.5")), decimal("0.6"));
+        assert_eq!(piecewise().apply(&decimal("0.8")), decimal("0.85"));
+    }
+
+    #[test]
+    fn piecewise_interpolates_between_points() {
+        // Halfway between 0.5 and 0.8 is halfway between 0.6 and 0.85.
+        assert_eq!(piecewise().apply(&decimal("0.65")), decimal("0.725"));
+    }
+
+    #[test]
+    fn piecewise_shifts_scores_outside_the_points() {
+        assert_eq!(piecewise().apply(&decimal("0.2")), decimal("0.3"));
+        assert_eq!(piecewise().apply(&decimal("0.9")), decimal("0.95"));
+    }
+
+    #[test]
+    fn additive_and_multiplicative_rules_apply() {
+        let additive = ScalingRule::Additive {
+            amount: decimal("-0.05"),
+        };
+        let multiplicative = ScalingRule::Multiplicative {
+            factor: decimal("1.1"),
+        };
+
+        assert_eq!(additive.apply(&decimal("0.5")), decimal("0.45"));
+        assert_eq!(multiplicative.apply(&decimal("0.5")), decimal("0.55"));
+    }
+
+    #[test]
+    fn validate_accepts_rules_at_the_bounds() {
+        for rule in [
+            ScalingRule::Additive {
+                amount: decimal("-1"),
+            },
+            ScalingRule::Multiplicative {
+                factor: decimal("5"),
+            },
+            ScalingRule::PiecewiseLinear {
+                points: vec![point("0", "0"), point("5", "5")],
+            },
+            ScalingRule::PiecewiseLinear {
+                points: vec![point("0.4", "0.5"), point("0.6", "0.5")],
+            },
+        ] {
+            assert_eq!(rule.validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn validate_rejects_invalid_rules() {
+        for rule in [
+            ScalingRule::Additive {
+                amount: decimal("1.01"),
+            },
+            ScalingRule::Multiplicative {
+                factor: decimal("0"),
+            },
+            ScalingRule::PiecewiseLinear {
+                points: vec![point("0.5", "0.6")],
+            },
+            ScalingRule::PiecewiseLinear {
+                points: vec![point("0.5", "0.6"), point("5.1", "5")],
+            },
+            ScalingRule::PiecewiseLinear {
+                points: vec![point("0.8", "0.85"), point("0.5", "0.6")],
+            },
+            ScalingRule::PiecewiseLinear {
+                points: vec![point("0.5", "0.6"), point("0.5", "0.7")],
+            },
+            ScalingRule::PiecewiseLinear {
+                points: vec![point("0.5", "0.7"), point("0.8", "0.6")],
+            },
+        ] {
+            assert!(rule.validate().is_err());
+        }
+    }
+}
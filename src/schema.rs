@@ -1,5 +1,15 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    calendar_feed (user_id) {
+        #[max_length = 191]
+        user_id -> Varchar,
+        #[max_length = 32]
+        token -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     course (id) {
         #[max_length = 25]
@@ -14,6 +24,16 @@ diesel::table! {
         course_code_number -> Nullable<Varchar>,
         #[max_length = 7]
         color -> Varchar,
+        #[max_length = 25]
+        origin_id -> Nullable<Varchar>,
+        share_grade_distribution -> Bool,
+        #[max_length = 25]
+        grading_scale_id -> Nullable<Varchar>,
+        credits -> Numeric,
+        hurdle -> Nullable<Numeric>,
+        target -> Nullable<Numeric>,
+        #[max_length = 32]
+        target_grade -> Nullable<Varchar>,
     }
 }
 
@@ -30,6 +50,13 @@ diesel::table! {
         subject_weighting -> Numeric,
         number_of_subcomponents_to_drop_lowest -> Int4,
         sequence_number -> Nullable<Int2>,
+        hurdle -> Nullable<Numeric>,
+        is_bonus -> Bool,
+        maximum_score -> Numeric,
+        late_penalty_per_day -> Nullable<Numeric>,
+        late_penalty_cap -> Nullable<Numeric>,
+        scaling -> Nullable<Json>,
+        is_scaling_enabled -> Bool,
     }
 }
 
@@ -44,6 +71,46 @@ diesel::table! {
         override_name -> Nullable<Varchar>,
         is_completed -> Bool,
         grade_value_percentage -> Numeric,
+        points_earned -> Nullable<Numeric>,
+        points_possible -> Nullable<Numeric>,
+        weighting -> Nullable<Numeric>,
+        due_at -> Nullable<Timestamptz>,
+        submitted_at -> Nullable<Timestamptz>,
+        extended_due_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    course_share (code) {
+        #[max_length = 8]
+        code -> Varchar,
+        #[max_length = 25]
+        course_id -> Varchar,
+        created_at -> Timestamptz,
+        expires_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    course_template (id) {
+        #[max_length = 25]
+        id -> Varchar,
+        #[max_length = 191]
+        user_id -> Varchar,
+        #[max_length = 191]
+        name -> Varchar,
+        #[max_length = 191]
+        institution -> Varchar,
+        #[max_length = 191]
+        long_name -> Nullable<Varchar>,
+        #[max_length = 10]
+        course_code_name -> Nullable<Varchar>,
+        #[max_length = 10]
+        course_code_number -> Nullable<Varchar>,
+        #[max_length = 7]
+        color -> Varchar,
+        structure -> Json,
+        created_at -> Timestamptz,
     }
 }
 
@@ -56,6 +123,41 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    grading_scale (id) {
+        #[max_length = 25]
+        id -> Varchar,
+        #[max_length = 191]
+        user_id -> Nullable<Varchar>,
+        #[max_length = 191]
+        name -> Varchar,
+        grade_map -> Json,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    scenario (id) {
+        #[max_length = 25]
+        id -> Varchar,
+        #[max_length = 25]
+        course_id -> Varchar,
+        #[max_length = 191]
+        name -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    scenario_subcomponent (scenario_id, subcomponent_id) {
+        #[max_length = 25]
+        scenario_id -> Varchar,
+        #[max_length = 25]
+        subcomponent_id -> Varchar,
+        grade_value_percentage -> Numeric,
+    }
+}
+
 diesel::table! {
     study_block (id) {
         #[max_length = 25]
@@ -69,15 +171,52 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    view_grant (id) {
+        #[max_length = 25]
+        id -> Varchar,
+        #[max_length = 32]
+        token -> Varchar,
+        #[max_length = 191]
+        user_id -> Varchar,
+        #[max_length = 25]
+        block_id -> Varchar,
+        #[max_length = 25]
+        course_id -> Nullable<Varchar>,
+        #[max_length = 191]
+        label -> Varchar,
+        created_at -> Timestamptz,
+        expires_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::joinable!(calendar_feed -> gk_user (user_id));
+diesel::joinable!(course -> grading_scale (grading_scale_id));
 diesel::joinable!(course -> study_block (block_id));
 diesel::joinable!(course_component -> course (course_id));
+diesel::joinable!(course_share -> course (course_id));
 diesel::joinable!(course_subcomponent -> course_component (component_id));
+diesel::joinable!(course_template -> gk_user (user_id));
+diesel::joinable!(grading_scale -> gk_user (user_id));
+diesel::joinable!(scenario -> course (course_id));
+diesel::joinable!(scenario_subcomponent -> course_subcomponent (subcomponent_id));
+diesel::joinable!(scenario_subcomponent -> scenario (scenario_id));
 diesel::joinable!(study_block -> gk_user (user_id));
+diesel::joinable!(view_grant -> course (course_id));
+diesel::joinable!(view_grant -> gk_user (user_id));
+diesel::joinable!(view_grant -> study_block (block_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    calendar_feed,
     course,
     course_component,
+    course_share,
     course_subcomponent,
+    course_template,
     gk_user,
+    grading_scale,
+    scenario,
+    scenario_subcomponent,
     study_block,
+    view_grant,
 );